@@ -1,59 +1,96 @@
-use crate::types::Generator;
-use crate::utils;
-use crate::AOC_RUNNER;
+use crate::utils::{self, to_generator};
 use aoc_runner_internal::{DayPart, Part};
 use proc_macro as pm;
+use proc_macro2 as pm2;
+use quote::quote;
 use syn::*;
 
 pub fn generator_impl(args: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
-    let (day, part, name) = utils::extract_meta(args);
-    let day = day
-        .to_string()
-        .parse()
-        .expect("generators must have defined day");
-    let part = part.and_then(|p| p.to_string().parse().ok());
+    match generator(args, input.clone()) {
+        Ok(expanded) => pm::TokenStream::from(expanded),
+        Err(e) => {
+            // Keeps the function, so that the error is not followed by others about it missing
+            let input: pm2::TokenStream = input.into();
+            let error = e.to_compile_error();
+
+            pm::TokenStream::from(quote! {
+                #input
+
+                #error
+            })
+        }
+    }
+}
+
+fn generator(args: pm::TokenStream, input: pm::TokenStream) -> Result<pm2::TokenStream> {
+    let (day, part, name) = utils::extract_meta(args)?;
+    let day = utils::parse_day(&day)?;
+    let part = part.as_ref().map(utils::parse_part).transpose()?;
     let name = name.map(|i| i.to_string());
 
-    let input_cloned = input.clone();
+    let input: ItemFn = syn::parse(input)?;
 
-    let input = parse_macro_input!(input as ItemFn);
+    let original_fn = input.clone();
 
     let fn_name = input.sig.ident;
     let out_t = if let ReturnType::Type(_, p) = input.sig.output {
         p
     } else {
-        panic!("cannot find output type for {}", fn_name)
+        return Err(Error::new(
+            fn_name.span(),
+            "generators must return the output given to the solutions",
+        ));
     };
 
-    let (special_type, out_t) = if let Some((ty, inner)) = utils::extract_result(&*out_t) {
-        (Some(ty), Box::new(inner))
-    } else {
-        (None, out_t)
+    let generator_name = fn_name.to_string();
+
+    let parts = match part {
+        Some(p) => vec![p],
+        None => vec![Part(1), Part(2)],
     };
 
-    AOC_RUNNER.with(|map| {
-        let mut map = map
-            .borrow_mut()
-            .expect("failed to borrow shared map from generator");
-
-        let mut register = |p: Part| {
-            let runner = map
-                .entry(DayPart {
-                    day,
-                    part: p,
-                    name: name.clone(),
-                })
-                .or_default();
-            runner.with_generator(Generator::new(&fn_name, &out_t, special_type));
-        };
-
-        if let Some(p) = part {
-            register(p);
-        } else {
-            register(Part(1));
-            register(Part(2));
-        }
-    });
+    // The solutions of the same module look the generator up by its day & part,
+    // a generator declared twice for the same solutions is a duplicate definition
+    let impls: pm2::TokenStream = parts
+        .into_iter()
+        .map(|part| {
+            let dp = DayPart {
+                day,
+                part,
+                name: name.clone(),
+            };
+            let generator_t = to_generator(&dp, fn_name.span());
+
+            quote! {
+                #[doc(hidden)]
+                #[allow(dead_code)]
+                struct #generator_t;
+
+                impl aoc_runner::Generator for #generator_t {
+                    type Output = #out_t;
+
+                    const NAME: Option<&'static str> = Some(#generator_name);
+
+                    const PATH: Option<&'static str> = Some(concat!(module_path!(), "::", #generator_name));
+
+                    fn generate(
+                        input: &aoc_runner::ArcStr,
+                    ) -> Result<std::rc::Rc<dyn std::any::Any>, Box<dyn std::error::Error>> {
+                        use aoc_runner::{FallibleGenerated as _, PlainGenerated as _};
+                        use std::borrow::Borrow;
+
+                        // Method resolution picks `FallibleGenerated` for `IntoGenerated` types, `PlainGenerated` for the others
+                        let generated = (&aoc_runner::GeneratorOutput::new( #fn_name(input.borrow()) )).into_generated()?;
+                        Ok(std::rc::Rc::new(generated))
+                    }
+                }
+            }
+        })
+        .collect();
+
+    Ok(quote! {
+        #original_fn
 
-    input_cloned
+        #impls
+    })
 }
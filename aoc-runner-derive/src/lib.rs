@@ -8,22 +8,16 @@ extern crate quote;
 extern crate syn;
 
 mod generator;
-mod map;
 mod out;
 mod runner;
 mod types;
 mod utils;
 
-use crate::map::Map;
 use crate::utils::is_rls;
 use proc_macro as pm;
 use proc_macro2 as pm2;
 use quote::quote;
 
-thread_local! {
-    static AOC_RUNNER: Map = Map::new();
-}
-
 #[proc_macro_attribute]
 /// # Solution meta
 ///
@@ -36,14 +30,44 @@ thread_local! {
 /// The function must take a single parameter : a `&str` or a `&[u8]`, unless you use a [generator]
 /// and return any type implementing `Display`.
 ///
+/// With a generator, the solution can also take the generator output by value (`Vec<T>`) or
+/// mutably (`&mut Grid`, `&mut [T]`), as long as it implements `Clone` : each run gets its own copy.
+/// Benchmarks make that copy before starting the clock.
+///
+/// ## Params
+///
+/// Constants that differ between the examples and the real input can be given as params :
+/// `#[aoc(day18, part1, params(size = 71, steps = 1024))]`. The function then takes a last parameter,
+/// a struct with those fields (by value or by reference), filled with these values.
+/// They can be overridden at runtime with `cargo aoc --param size=7`, or the `AOC_PARAMS` environment variable
+/// (`AOC_PARAMS=size=7,steps=12`).
+///
+/// ## Both parts
+///
+/// A function solving both parts at once can be flagged `#[aoc(day8, both)]` (or `#[aoc(day8, both, OnePass)]`),
+/// and return a tuple `(part1, part2)` (or an `Option`/`Result` of it). It is then a solution of each part,
+/// and `cargo aoc` reports both answers from a single call when running both parts.
+///
+/// ## State of part 1
+///
+/// When part 2 builds on what part 1 computed, the default part 1 solution can return a tuple `(answer, state)`
+/// (or an `Option`/`Result` of it), and part 2 solutions take the state as a second parameter (by value or by reference) :
+/// `fn part2(input: &Input, state: &State) -> u32`. Part 2 then reuses the state given when part 1 ran, or runs part 1
+/// once while building its runner, like a generator, so that part 1 is not timed along with part 2.
+/// Both parts must use the same generator, if any.
+///
 /// ## Results & Options
 ///
-/// Since 0.2.0, you can output `Result` & `Option` from solution function, with the following constraints :
-///  - the output type must be named `Result` or `Option`, `type CustomResult<T> = Result<T, CustomError>;` cannot be used in return position.
-///  - the first generic parameter must implement `Display`
-///  - for `Result`s, the error must implement `Into<std::error::Error>`
+/// A solution can also output any type implementing `IntoAnswer`, to report a failure. It is implemented for :
+///  - `Option<T>`, where `T` implements `Display`
+///  - `Result<T, E>`, where `T` implements `Display` and `E` implements `Into<Box<dyn std::error::Error>>`
+///    (like `anyhow::Error`)
 ///
-/// You still can use a path before the `Result`/`Option`, like this : `std::io::Result<i32>`
+/// Aliases work too (`std::io::Result<i32>`, `type CustomResult<T> = Result<T, CustomError>;`), and so does `impl Display`.
+///
+/// ## Note
+/// A day & part (& name) has a single solution in the crate : a second one fails to compile,
+/// with duplicate definitions of `day1_part1_is_already_solved`.
 ///
 /// [generator]: attr.aoc_generator.html
 pub fn aoc(args: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
@@ -73,16 +97,20 @@ pub fn aoc(args: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
 ///
 /// The corresponding solutions now take any parameter for which `Borrow` is implemented.
 ///
+/// When running several solutions using the same generator (like both parts of a day), the generator
+/// only runs once, and its output is shared by the solutions.
+///
 /// ## Results & Options
 ///
-/// Since 0.2.0, you can output `Result` & `Option` from generator function, with the following constraints :
-///  - the output type must be named `Result` or `Option`, `type CustomResult<T> = Result<T, CustomError>;` cannot be used in return position.
-///  - for `Result`s, the error must implement `Into<std::error::Error>`
+/// A generator can also output any type implementing `IntoGenerated`, to report a failure. The solutions then take
+/// what it holds. It is implemented for :
+///  - `Option<T>`
+///  - `Result<T, E>`, where `E` implements `Into<Box<dyn std::error::Error>>` (like `anyhow::Error`)
 ///
-/// You still can use a path before the `Result`/`Option`, like this : `std::io::Result<i32>`
+/// Aliases work too (`std::io::Result<i32>`, `type CustomResult<T> = Result<T, CustomError>;`).
 ///
 /// ## Note
-/// A generator must be declared before it's solutions.
+/// A generator applies to the solutions declared in the same module, above or below it.
 ///
 pub fn aoc_generator(args: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
     if is_rls() {
@@ -99,10 +127,17 @@ pub fn aoc_generator(args: pm::TokenStream, input: pm::TokenStream) -> pm::Token
 #[proc_macro]
 /// # Library declaration
 ///
-/// This macro must be at the end of lib.rs
+/// It can be used anywhere in lib.rs, the solutions of the crate are collected when linking.
 ///
 /// ## Usage
 /// `aoc_lib! { year = 2018 }`
+///
+/// ## Answer tests
+///
+/// With `aoc_lib! { year = 2018, tests = true }`, a `#[test]` is generated for each solution, next to it.
+/// It runs the solution over `input/{year}/dayN.txt` and checks its result against the known answer
+/// recorded in `answers/{year}.toml` (see `cargo aoc verify`).
+/// Solutions without input or known answer are skipped.
 pub fn aoc_lib(input: pm::TokenStream) -> pm::TokenStream {
     if is_rls() {
         return pm::TokenStream::new();
@@ -114,12 +149,15 @@ pub fn aoc_lib(input: pm::TokenStream) -> pm::TokenStream {
 #[proc_macro]
 /// # Main declaration
 ///
-/// This macro must be at the end of main.rs
+/// The binary runs every solution over `input/{year}/dayN.txt`, read from the current directory when it runs.
+/// Its command line picks the solutions and inputs, see `--help` :
+/// `cargo run --release -- -d 5 -p 2 -i example.txt`
 ///
 /// ## Usage
 /// `aoc_main` has 2 forms :
 ///  - as a standalone binary : `aoc_main! { year = 2018 }`
-///  - as a link to a library : `aoc_main! { lib = advent_of_code_2018 }` (you must had `extern crate advent_of_code_2018;` before)
+///  - as a link to a library : `aoc_main! { lib = advent_of_code_2018 }` (you must had `extern crate advent_of_code_2018;` before),
+///    running the solutions of the year given to its `aoc_lib!`, or of `year` if given too
 pub fn aoc_main(input: pm::TokenStream) -> pm::TokenStream {
     if is_rls() {
         return pm::TokenStream::from(quote! { fn main() {} });
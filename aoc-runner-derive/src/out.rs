@@ -1,19 +1,15 @@
-use crate::map::InnerMap;
-use crate::utils::{to_camelcase, to_input, to_snakecase};
-use crate::AOC_RUNNER;
-use aoc_runner_internal::{DayParts, DayPartsBuilder};
 use proc_macro as pm;
 use proc_macro2 as pm2;
 use quote::quote;
-use std::error;
 use syn::parse::{Error as ParseError, Parse, ParseStream, Parser};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
-use syn::{LitInt, Token};
+use syn::{LitBool, LitInt, Token};
 
 mod kw {
     use syn::custom_keyword;
     custom_keyword!(lib);
+    custom_keyword!(tests);
     custom_keyword!(year);
 }
 
@@ -28,6 +24,11 @@ enum LibMacroArg {
         _eq_tok: Token![=],
         value: pm2::Ident,
     },
+    Tests {
+        type_tok: kw::tests,
+        _eq_tok: Token![=],
+        value: LitBool,
+    },
 }
 
 impl LibMacroArg {
@@ -52,6 +53,12 @@ impl Parse for LibMacroArg {
                 _eq_tok: input.parse()?,
                 value: input.parse()?,
             })
+        } else if lookahead.peek(kw::tests) {
+            Ok(LibMacroArg::Tests {
+                type_tok: input.parse::<kw::tests>()?,
+                _eq_tok: input.parse()?,
+                value: input.parse()?,
+            })
         } else {
             Err(lookahead.error())
         }
@@ -61,13 +68,14 @@ impl Parse for LibMacroArg {
 #[derive(Debug)]
 struct LibInfos {
     year: u32,
+    tests: bool,
 }
 
 #[derive(Debug)]
 enum MainInfos {
     Ref {
         lib: pm2::Ident,
-        #[allow(dead_code)]
+        /// Only runs the solutions of this year, instead of the one of the library
         year: Option<u32>,
     },
     Standalone {
@@ -76,218 +84,96 @@ enum MainInfos {
 }
 
 pub fn lib_impl(input: pm::TokenStream) -> pm::TokenStream {
-    let infos = parse_lib_infos(input);
-    if let Err(e) = infos {
-        return e.to_compile_error().into();
-    }
-    let infos = infos.unwrap();
-
-    AOC_RUNNER.with(|map| {
-        let map = map.consume().expect("failed to consume map from lib");
-
-        let year = infos.year;
+    let infos = match parse_lib_infos(input) {
+        Ok(infos) => infos,
+        Err(e) => return e.to_compile_error().into(),
+    };
 
-        write_infos(&map, year).expect("failed to write infos from lib");
-
-        pm::TokenStream::from(headers(&map, year))
-    })
+    pm::TokenStream::from(headers(infos.year, infos.tests))
 }
 
 pub fn main_impl(input: pm::TokenStream) -> pm::TokenStream {
-    let infos = parse_main_infos(input);
-    // TODO: put this in upper function and properly wrap below?
-    if let Err(e) = infos {
-        return e.to_compile_error().into();
-    }
-    let infos = infos.unwrap();
+    let infos = match parse_main_infos(input) {
+        Ok(infos) => infos,
+        Err(e) => return e.to_compile_error().into(),
+    };
+
+    let expanded = match infos {
+        MainInfos::Ref { lib, year } => {
+            let body = match year {
+                Some(year) => body(quote! { #year }),
+                None => body(quote! { YEAR }),
+            };
 
-    AOC_RUNNER.with(|map| {
-        let map = map.consume().expect("failed to consume map from main");
+            quote! {
+                use #lib::*;
 
-        let expanded = match infos {
-            MainInfos::Ref { lib, .. } => {
-                let infos =
-                    read_infos(lib.to_string()).expect("failed to read infos from ref main");
-                body(&infos, Some(lib))
+                #body
             }
-            MainInfos::Standalone { year } => {
-                let infos =
-                    write_infos(&map, year).expect("failed to write infos from standalone main");
-                let headers = headers(&map, year);
-                let body = body(&infos, None);
+        }
+        MainInfos::Standalone { year } => {
+            let headers = headers(year, false);
+            let body = body(quote! { YEAR });
 
-                quote! {
-                    #headers
+            quote! {
+                #headers
 
-                    #body
-                }
+                #body
             }
-        };
+        }
+    };
 
-        pm::TokenStream::from(expanded)
-    })
+    pm::TokenStream::from(expanded)
 }
 
-fn headers(map: &InnerMap, year: u32) -> pm2::TokenStream {
-    let traits_impl: pm2::TokenStream = map
-        .keys()
-        .map(|dp| {
-            let snake = to_snakecase(dp);
-            let camel = to_camelcase(dp);
-
-            quote! {
-                #[doc(hidden)]
-                pub trait #camel {
-                    fn #snake(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>>;
-                }
-            }
-        })
-        .collect();
+/// The `Factory` the solutions add their runners to, wherever they are declared.
+/// Each solution adds them to its own `Factory<RunnerStruct>`, and a marker to `Factory` itself,
+/// so that a solution declared twice is reported once, by its marker.
+///
+/// Each solution also hands the test of its answer to `__aoc_answer_test!`, that keeps it with `tests = true`.
+fn headers(year: u32, tests: bool) -> pm2::TokenStream {
+    let answer_test = if tests {
+        quote! { $($test)* }
+    } else {
+        pm2::TokenStream::new()
+    };
 
     quote! {
         pub use self::aoc_factory::*;
 
         #[allow(unused)]
         mod aoc_factory {
-            use aoc_runner::{Runner, ArcStr};
-            use std::error::Error;
+            #[doc(hidden)]
+            pub const YEAR : u32 = #year;
 
             #[doc(hidden)]
-            pub static YEAR : u32 = #year;
+            pub struct Factory<S = ()>(std::marker::PhantomData<S>);
 
             #[doc(hidden)]
-            pub struct Factory();
+            macro_rules! __aoc_answer_test {
+                ($($test:tt)*) => { #answer_test };
+            }
 
-            #traits_impl
+            #[doc(hidden)]
+            pub(crate) use __aoc_answer_test;
         }
     }
 }
 
-fn body(infos: &DayParts, lib: Option<pm2::Ident>) -> pm2::TokenStream {
-    let mut days: Vec<_> = infos.iter().map(|dp| dp.day).collect();
-    days.sort();
-    days.dedup();
-
-    let inputs: pm2::TokenStream = days
-        .into_iter()
-        .map(|d| {
-            let name = to_input(d);
-            let input = format!("../input/{}/day{}.txt", infos.year, d.0);
-
-            quote! { let #name = ArcStr::from(include_str!(#input)); }
-        })
-        .collect();
-
-    let body : pm2::TokenStream = infos.iter().map(|dp| {
-        let identifier = to_snakecase(dp);
-        let (pattern, err) = if let Some(n) = &dp.name {
-            (
-                format!(
-                    "Day {} - Part {} - {}: {{}}\n\tgenerator: {{:?}},\n\trunner: {{:?}}\n",
-                    dp.day.0, dp.part.0, n
-                ),
-                format! (
-                    "Day {} - Part {} - {}: FAILED while {{}}:\n{{:#?}}\n",
-                    dp.day.0, dp.part.0, n
-                )
-            )
-        } else {
-            (
-                format!(
-                    "Day {} - Part {}: {{}}\n\tgenerator: {{:?}},\n\trunner: {{:?}}\n",
-                    dp.day.0, dp.part.0
-                ),
-                format! (
-                    "Day {} - Part {}: FAILED while {{}}:\n{{:#?}}\n",
-                    dp.day.0, dp.part.0
-                )
-            )
-        };
-
-        let input = to_input(dp.day);
-
-        quote! {
-            {
-                let start_time = Instant::now();
-
-                match Factory::#identifier(#input.clone()) {
-                    Ok(runner) => {
-                        let inter_time = Instant::now();
-
-                        match runner.try_run() {
-                            Ok(result) => {
-                                let final_time = Instant::now();
-                                println!(#pattern, result, (inter_time - start_time), (final_time - inter_time));
-                            },
-                            Err(e) => eprintln!(#err, "running", e)
-                        }
-                    },
-                    Err(e) => eprintln!(#err, "generating", e)
-                }
-            }
-        }
-    }).collect();
-
-    if let Some(lib) = lib {
-        quote! {
-            use #lib::*;
-
-            fn main() {
-                use aoc_runner::ArcStr;
-                use std::time::{Duration, Instant};
-
-                #inputs
-
-                println!("Advent of code {}", YEAR);
-
-                #body
-            }
-        }
-    } else {
-        quote! {
-            fn main() {
-                use aoc_runner::ArcStr;
-                use std::time::{Duration, Instant};
-
-
-                #inputs
-
-                println!("Advent of code {}", YEAR);
-
-                #body
-            }
+/// The solutions of `year` are collected when linking the binary, and picked from its command line
+fn body(year: pm2::TokenStream) -> pm2::TokenStream {
+    quote! {
+        fn main() {
+            aoc_runner::main(#year);
         }
     }
 }
 
-fn write_infos(map: &InnerMap, year: u32) -> Result<DayParts, Box<dyn error::Error>> {
-    let mut day_parts = map
-        .iter()
-        .filter_map(|(dp, runner)| {
-            if runner.solver.is_some() {
-                Some(dp.clone())
-            } else {
-                None
-            }
-        })
-        .collect::<DayPartsBuilder>()
-        .with_year(year);
-
-    day_parts.sort();
-
-    day_parts.save()?;
-
-    Ok(day_parts)
-}
-
-fn read_infos(crate_name: String) -> Result<DayParts, Box<dyn error::Error>> {
-    DayParts::load(crate_name, None)
-}
-
 fn parse_lib_infos(infos: pm::TokenStream) -> Result<LibInfos, ParseError> {
     let args: Vec<LibMacroArg> = LibMacroArg::get_from_stream(&infos)?.collect();
 
     let mut year = None;
+    let mut tests = None;
     for arg in args {
         match arg {
             LibMacroArg::Year {
@@ -308,6 +194,18 @@ fn parse_lib_infos(infos: pm::TokenStream) -> Result<LibInfos, ParseError> {
                     "'lib' is only allowed in `aoc_main`!",
                 ));
             }
+            LibMacroArg::Tests {
+                type_tok, value, ..
+            } => {
+                if tests.is_some() {
+                    return Err(ParseError::new(
+                        type_tok.span,
+                        "Tests cannot be given multiple times!",
+                    ));
+                } else {
+                    tests = Some(value.value);
+                }
+            }
         }
     }
     if year.is_none() {
@@ -316,6 +214,7 @@ fn parse_lib_infos(infos: pm::TokenStream) -> Result<LibInfos, ParseError> {
     }
     Ok(LibInfos {
         year: year.unwrap(),
+        tests: tests.unwrap_or(false),
     })
 }
 
@@ -350,6 +249,12 @@ fn parse_main_infos(infos: pm::TokenStream) -> Result<MainInfos, ParseError> {
                     lib_ref = Some(value);
                 }
             }
+            LibMacroArg::Tests { type_tok, .. } => {
+                return Err(ParseError::new(
+                    type_tok.span,
+                    "'tests' is only allowed in `aoc_lib`!",
+                ));
+            }
         }
     }
 
@@ -1,196 +1,638 @@
-use crate::types::{Generator, Solver, SpecialType};
-use crate::utils::{self, extract_result, to_camelcase, to_snakecase};
-use crate::AOC_RUNNER;
-use aoc_runner_internal::DayPart;
+use crate::types::{InputKind, Param, Solver};
+use crate::utils::{self, extract_input_kind, to_generator, to_shared, to_snakecase, to_stated};
+use aoc_runner_internal::{DayPart, Part};
 use proc_macro as pm;
 use proc_macro2 as pm2;
-use quote::quote;
+use quote::{quote, quote_spanned};
+use syn::spanned::Spanned;
 use syn::*;
 
+/// The params of a solver, and how it takes them
+struct SolverParams {
+    ty: Type,
+    kind: InputKind,
+    params: Vec<Param>,
+}
+
+/// The state given by part 1 to a part 2 solver, and how it takes it
+struct SolverState {
+    ty: Type,
+    kind: InputKind,
+}
+
+/// Whether the runner keeps the state given by part 1 when it runs, for the part 2 solutions taking it
+fn gives_state(solver: &Solver, dp: &DayPart) -> bool {
+    dp.part == Part(1) && dp.name.is_none() && !solver.both
+}
+
+/// Splits the type of an extra argument of a solver, taken by value or by reference
+fn extra_arg(arg: &FnArg, what: &str) -> Result<(InputKind, Type)> {
+    match arg {
+        FnArg::Typed(arg) => match &*arg.ty {
+            Type::Reference(r) if r.mutability.is_none() => Ok((InputKind::Ref, (*r.elem).clone())),
+            Type::Reference(_) => Err(Error::new_spanned(
+                &arg.ty,
+                format!("{} must be taken by value or by reference", what),
+            )),
+            ty => Ok((InputKind::Owned, ty.clone())),
+        },
+        _ => Err(Error::new_spanned(arg, "solvers can't take self")),
+    }
+}
+
 pub fn runner_impl(args: pm::TokenStream, input: pm::TokenStream) -> pm::TokenStream {
-    let (day, part, name) = utils::extract_meta(args);
-    let day = day
-        .to_string()
-        .parse()
-        .expect("runners must have a defined day");
-    let part = part
-        .expect("runners must have a defined part")
-        .to_string()
-        .parse()
-        .expect("runners must have a defined part");
-    let name = name.map(|i| i.to_string());
+    match solution(args, input.clone()) {
+        Ok(expanded) => pm::TokenStream::from(expanded),
+        Err(e) => {
+            // Keeps the function, so that the error is not followed by others about it missing
+            let input: pm2::TokenStream = input.into();
+            let error = e.to_compile_error();
+
+            pm::TokenStream::from(quote! {
+                #input
+
+                #error
+            })
+        }
+    }
+}
 
-    let dp = DayPart { day, part, name };
+fn solution(args: pm::TokenStream, input: pm::TokenStream) -> Result<pm2::TokenStream> {
+    let (args, params) = utils::extract_params(args)?;
+    let (day, part, name) = utils::extract_meta(args)?;
+    let day = utils::parse_day(&day)?;
+    let part = part.ok_or_else(|| {
+        Error::new(
+            pm::Span::call_site().into(),
+            "expected a part, like `#[aoc(day1, part1)]`",
+        )
+    })?;
+    // A solution of both parts registers as each of them
+    let (parts, both) = if part == "both" {
+        (vec![Part(1), Part(2)], true)
+    } else {
+        (vec![utils::parse_part(&part)?], false)
+    };
+    let name = name.map(|i| i.to_string());
 
-    let input = parse_macro_input!(input as ItemFn);
+    let input: ItemFn = syn::parse(input)?;
 
     let original_fn = input.clone();
 
-    let fn_name = input.sig.ident;
-    let out_t = if let ReturnType::Type(_, p) = input.sig.output {
-        p
+    let input_ty = match input.sig.inputs.first() {
+        Some(FnArg::Typed(arg)) => (*arg.ty).clone(),
+        _ => {
+            return Err(Error::new_spanned(
+                &input.sig,
+                "solvers must take their input as first parameter",
+            ))
+        }
+    };
+    let input_kind = extract_input_kind(&input_ty);
+
+    if let ReturnType::Default = input.sig.output {
+        return Err(Error::new_spanned(
+            &input.sig,
+            "solvers must return their answer",
+        ));
+    }
+
+    // Arguments are the input, then the state given by part 1 (for part 2 only), then the params
+    let mut extra_args = input.sig.inputs.iter().skip(1);
+
+    let state = if parts == [Part(2)] && extra_args.len() > usize::from(!params.is_empty()) {
+        let (kind, ty) = extra_arg(extra_args.next().unwrap(), "the state of part 1")?;
+        Some(SolverState { ty, kind })
     } else {
-        panic!()
+        None
     };
 
-    let (special_type, out_t) = if let Some((ty, inner)) = extract_result(&*out_t) {
-        (Some(ty), Box::new(inner))
+    let params = if params.is_empty() {
+        None
     } else {
-        (None, out_t)
+        let arg = extra_args.next().ok_or_else(|| {
+            Error::new_spanned(
+                &input.sig,
+                "solvers with params must take them as last parameter",
+            )
+        })?;
+        let (kind, ty) = extra_arg(arg, "params")?;
+
+        Some(SolverParams { ty, kind, params })
     };
 
-    let def = AOC_RUNNER.with(|map| {
-        let mut map = map
-            .borrow_mut()
-            .expect("failed to borrow shared map from runner");
+    if let Some(arg) = extra_args.next() {
+        return Err(Error::new_spanned(
+            arg,
+            "solvers take their input, then the state of part 1 (for part 2), then their params",
+        ));
+    }
 
-        let dp = dp.clone();
-        let def = dp.without_name();
+    let fn_name = input.sig.ident;
 
-        if !map.contains_key(&dp) && map.contains_key(&def) {
-            let mut val = map[&def].clone();
-            val.solver = None;
-            map.insert(dp.clone(), val);
-        }
+    let mods = parts
+        .into_iter()
+        .map(|part| {
+            let dp = DayPart {
+                day,
+                part,
+                name: name.clone(),
+            };
+            let solver = Solver::new(&fn_name, input_kind, both);
+
+            solution_mod(dp, solver, &input_ty, state.as_ref(), params.as_ref())
+        })
+        .collect::<Result<pm2::TokenStream>>()?;
+
+    Ok(quote! {
+        #original_fn
 
-        let runner = map.entry(dp).or_default();
+        #mods
+    })
+}
 
-        runner.with_solver(Solver::new(&fn_name, &out_t, special_type));
+/// Builds the module implementing the `Runner` of a solution, and registers it
+fn solution_mod(
+    dp: DayPart,
+    solver: Solver,
+    input_ty: &Type,
+    state: Option<&SolverState>,
+    params: Option<&SolverParams>,
+) -> Result<pm2::TokenStream> {
+    let mod_name = to_snakecase(&dp);
+    let shared_name = to_shared(&dp);
+    let generator_t = to_generator(&dp, pm2::Span::call_site());
+    // The runners of a solution are added to `Factory<RunnerStruct>`, this marker to `Factory` itself :
+    // a second solution of the same day, part & name is only reported as
+    // "duplicate definitions with name `day2_part1_is_already_solved`", pointing at both solvers
+    let already_solved = Ident::new(&format!("{}_is_already_solved", mod_name), solver.span);
+    let already_solved = quote_spanned! {solver.span=>
+        #[doc(hidden)]
+        #[allow(non_upper_case_globals)]
+        pub const #already_solved: () = ();
+    };
 
-        let derive = build_derive(runner.solver.as_ref().unwrap(), runner.generator.as_ref());
+    // Named solutions fall back on the generator of the default solution, which falls back on the input as is.
+    // A generator declared in the module shadows these glob imports.
+    let fallback = if dp.name.is_some() {
+        let fallback_mod = Ident::new(&format!("__aoc_fallback_{}", mod_name), pm2::Span::call_site());
+        let default_t = to_generator(&dp.without_name(), pm2::Span::call_site());
 
-        if let Some(generator) = &runner.generator {
-            let gen_out_t = &generator.get_out_t();
+        quote! {
+            #[doc(hidden)]
+            mod #fallback_mod {
+                pub(super) use super::#default_t as #generator_t;
+            }
 
-            quote! {
-                pub struct RunnerStruct {
-                    input: #gen_out_t,
-                    output: PhantomData<#out_t>,
-                }
+            #[allow(unused_imports)]
+            use self::#fallback_mod::*;
+        }
+    } else {
+        pm2::TokenStream::new()
+    };
 
-                #derive
-            }
-        } else {
-            quote! {
-                pub struct RunnerStruct {
-                    input: ArcStr,
-                    output: PhantomData<#out_t>,
-                }
+    let derive = build_derive(&solver, input_ty, state, params, &dp)?;
+
+    let state_field = state.map(|s| {
+        let state_t = &s.ty;
+        quote! { state: Rc<#state_t>, }
+    });
+    let given_state_field = if gives_state(&solver, &dp) {
+        quote! { given_state: RefCell<Option<Rc<dyn Any>>>, }
+    } else {
+        pm2::TokenStream::new()
+    };
 
-                #derive
+    // Part 2 solutions can be built from the state part 1 gave when it ran
+    let (stated, state_runner) = if dp.part == Part(2) {
+        let stated_name = to_stated(&dp);
+        let stated = quote! {
+            #[doc(hidden)]
+            pub fn #stated_name(
+                input: ArcStr,
+                shared: Option<Rc<dyn Any>>,
+                state: Rc<dyn Any>,
+            ) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+                let input = match shared {
+                    Some(shared) => shared,
+                    None => __AocGenerator::generate(&input)?,
+                };
+                Ok(Box::new( RunnerStruct::from_state(input, state)? ))
             }
-        }
+        };
+
+        (stated, quote! { Some(crate::Factory::<RunnerStruct>::#stated_name) })
+    } else {
+        (pm2::TokenStream::new(), quote! { None })
+    };
+    let params_field = params.map(|p| {
+        let params_t = &p.ty;
+        quote! { params: #params_t, }
     });
 
-    let mod_name = to_snakecase(&dp);
-    let trait_name = to_camelcase(&dp);
+    let (day, part) = (dp.day.0, dp.part.0);
+    let name = match &dp.name {
+        Some(name) => quote! { Some(#name) },
+        None => quote! { None },
+    };
+    let both = solver.both;
 
-    pm::TokenStream::from(quote! {
-        #original_fn
+    Ok(quote! {
+        #[allow(unused_imports)]
+        use aoc_runner::no_generator::*;
+
+        #fallback
 
         #[allow(unused_imports)]
         mod #mod_name {
             use super::*;
-            use aoc_runner::{ArcStr, Runner};
-            use std::marker::PhantomData;
+            use aoc_runner::{ArcStr, GeneratedInput, Generator, Runner, SolverOutput};
+            use aoc_runner::{DisplayOutput as _, FallibleOutput as _, StatefulOutput as _, StatelessOutput as _};
+            use aoc_runner::{FallibleInput as _, PlainInput as _};
             use std::error::Error;
             use std::fmt::Display;
-            use std::borrow::Borrow;
-            use crate::{Factory, #trait_name};
+            use std::borrow::{Borrow, BorrowMut};
+            use std::any::Any;
+            use std::cell::RefCell;
+            use std::rc::Rc;
+
+            type __AocGenerator = super::#generator_t;
+            type __AocOutput = <__AocGenerator as Generator>::Output;
 
-            impl #trait_name for Factory {
-                fn #mod_name(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+            impl crate::Factory {
+                #already_solved
+            }
+
+            impl crate::Factory<RunnerStruct> {
+                #[doc(hidden)]
+                pub fn #mod_name(input: ArcStr) -> Result<Box<dyn Runner>, Box<dyn Error>> {
                     Ok(Box::new( RunnerStruct::try_gen(input)? ))
                 }
+
+                #[doc(hidden)]
+                pub fn #shared_name(input: Rc<dyn Any>) -> Result<Box<dyn Runner>, Box<dyn Error>> {
+                    Ok(Box::new( RunnerStruct::from_input(input)? ))
+                }
+
+                #stated
+            }
+
+            aoc_runner::inventory::submit! {
+                aoc_runner::Solution {
+                    year: crate::YEAR,
+                    day: #day,
+                    part: #part,
+                    name: #name,
+                    generator: <__AocGenerator as Generator>::NAME,
+                    generator_path: <__AocGenerator as Generator>::PATH,
+                    both: #both,
+                    runner: crate::Factory::<RunnerStruct>::#mod_name,
+                    shared_runner: crate::Factory::<RunnerStruct>::#shared_name,
+                    state_runner: #state_runner,
+                }
+            }
+
+            crate::__aoc_answer_test! {
+                #[test]
+                fn known_answer() {
+                    aoc_runner::test_answer(crate::YEAR, #day, #part, #name, env!("CARGO_MANIFEST_DIR"));
+                }
             }
 
-            #def
+            pub struct RunnerStruct {
+                input: Rc<dyn Any>,
+                #state_field
+                #given_state_field
+                #params_field
+            }
+
+            #derive
         }
     })
 }
 
-fn build_derive(solver: &Solver, generator: Option<&Generator>) -> pm2::TokenStream {
+fn build_derive(
+    solver: &Solver,
+    input_ty: &Type,
+    state: Option<&SolverState>,
+    params: Option<&SolverParams>,
+    dp: &DayPart,
+) -> Result<pm2::TokenStream> {
     let fn_runner = solver.get_name();
 
-    // Build the output, possibly using quasi-quotation
-    let input = if let Some(generator) = generator {
-        let fn_generator = generator.get_name();
-        quote! {
-            input: #fn_generator(input.borrow())
-        }
+    let given_state_init = if gives_state(solver, dp) {
+        quote! { given_state: RefCell::new(None), }
     } else {
-        quote! {
-            input
+        pm2::TokenStream::new()
+    };
+
+    // Params are read once, overridden by the `AOC_PARAMS` environment variable
+    let params_init = params.map(|p| {
+        let params_t = &p.ty;
+        let fields: pm2::TokenStream = p
+            .params
+            .iter()
+            .map(|Param { name, default }| {
+                let key = name.to_string();
+                let default = match default {
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(_), ..
+                    }) => quote! { #default.into() },
+                    _ => quote! { #default },
+                };
+
+                quote! { #name: aoc_runner::param(#key)?.unwrap_or_else(|| #default), }
+            })
+            .collect();
+
+        quote! { params: #params_t { #fields }, }
+    });
+
+    let gen = quote! {
+        fn gen(input: ArcStr) -> Self {
+            Self::try_gen(input).expect("failed to generate input")
+        }
+
+        fn try_gen(input: ArcStr) -> Result<Self, Box<dyn Error>> {
+            Self::from_input( __AocGenerator::generate(&input)? )
         }
     };
 
-    let gen = if let Some(t) = generator.and_then(|g| g.special_type) {
-        let input = match t {
-            SpecialType::Result => quote! { #input? },
-            SpecialType::Option => quote! { #input.ok_or("generator produce no value")? },
-        };
+    // The generator gives its input to the solvers as `dyn Any`, checked against its output when building the runner.
+    // Method resolution picks `FallibleInput` for `IntoGenerated` outputs, `PlainInput` for the others.
+    // A generator output that doesn't fit is reported once, on the type of the solver parameter.
+    let span = input_ty.span();
+    let generated = quote_spanned! { span => (&GeneratedInput::<__AocOutput>::new()) };
+    let input = quote_spanned! { span => #generated.of(&*self.input).expect("input of another generator") };
+
+    // Solvers taking the generator output by value or by `&mut` get a copy of it, through the trait taking it.
+    let (day, part) = (dp.day.0, dp.part.0);
+    let taken_t = match input_ty {
+        Type::Reference(r) => &*r.elem,
+        ty => ty,
+    };
+    let input_trait = match solver.input_kind {
+        InputKind::Ref => quote_spanned! { span => aoc_runner::RefInput::<#taken_t, #day, #part> },
+        InputKind::Mut => quote_spanned! { span => aoc_runner::MutInput::<#taken_t, #day, #part> },
+        InputKind::Owned => quote_spanned! { span => aoc_runner::OwnedInput::<#taken_t, #day, #part> },
+    };
+    let copy_input = quote_spanned! { span => #input_trait::copy_input(#input) };
+    let arg = match solver.input_kind {
+        InputKind::Ref => quote_spanned! { span => #input_trait::input_ref(#input) },
+        InputKind::Mut => quote_spanned! { span => #input_trait::input_mut(&mut #copy_input) },
+        InputKind::Owned => copy_input.clone(),
+    };
 
+    let state_arg = state.map(|s| match s.kind {
+        InputKind::Ref => quote! { , &*self.state },
+        _ => quote! { , (*self.state).clone() },
+    });
+    let params_arg = params.map(|p| match p.kind {
+        InputKind::Ref => quote! { , &self.params },
+        _ => quote! { , self.params.clone() },
+    });
+
+    // Method resolution picks `StatefulOutput` for `(answer, state)` tuples, then `FallibleOutput`
+    // for `IntoAnswer` types, `DisplayOutput` for the others
+    let call = |arg: pm2::TokenStream| {
+        quote! { (&SolverOutput::new( #fn_runner(#arg #state_arg #params_arg) )).split_state() }
+    };
+    // Solutions of both parts give the answer of part 2 as the state of part 1,
+    // which is only picked when the answer of part 1 is not a failure
+    let both_call = |arg: pm2::TokenStream| {
+        quote! { aoc_runner::StatefulOutput::split_state(&SolverOutput::new( #fn_runner(#arg #params_arg) )) }
+    };
+    let answer = |arg: pm2::TokenStream| match (solver.both, dp.part) {
+        (true, Part(2)) => {
+            let call = both_call(arg);
+            quote! {{
+                let (first, second) = #call;
+                (&SolverOutput::new(first)).into_answer().and_then(|_| {
+                    (&SolverOutput::new(second.expect("part 2 is answered along with part 1"))).into_answer()
+                })
+            }}
+        }
+        _ => {
+            let call = call(arg);
+            quote! {{
+                let (output, _) = #call;
+                (&SolverOutput::new(output)).into_answer()
+            }}
+        }
+    };
+    let run_answer = answer(arg.clone());
+
+    // Part 2 shares the state it was built with, for the other part 2 solutions
+    let shared_state = state.map(|_| {
         quote! {
-            fn gen(input: ArcStr) -> Self {
-                Self::try_gen(input).expect("failed to generate input")
+            fn shared_state(&self) -> Option<Rc<dyn Any>> {
+                Some(self.state.clone())
             }
+        }
+    });
 
-            fn try_gen(input: ArcStr) -> Result<Self, Box<dyn Error>> {
-                Ok( RunnerStruct {
-                    #input,
-                    output: PhantomData,
-                } )
+    let check_input = quote! {
+        #generated.of(&*input).ok_or("shared input of another generator")?;
+    };
+
+    // Part 1 runs once while building the runner, so that its state is ready before part 2 is timed,
+    // unless the runner is built from the state part 1 gave when it ran
+    let constructors = match state {
+        Some(s) => {
+            let state_t = &s.ty;
+            let part1 = DayPart {
+                part: Part(1),
+                ..dp.without_name()
+            };
+            let part1_fn = to_shared(&part1);
+
+            quote! {
+                fn from_input(input: Rc<dyn Any>) -> Result<Self, Box<dyn Error>> {
+                    let state = crate::Factory::#part1_fn(input.clone())?.try_state()?;
+                    Self::from_state(input, state)
+                }
+
+                fn from_state(input: Rc<dyn Any>, state: Rc<dyn Any>) -> Result<Self, Box<dyn Error>> {
+                    #check_input
+                    let state = state
+                        .downcast::<#state_t>()
+                        .map_err(|_| "part 1 gives a state of another type")?;
+
+                    Ok( RunnerStruct {
+                        input,
+                        state,
+                        #params_init
+                    } )
+                }
             }
         }
-    } else {
-        quote! {
-            fn gen(input: ArcStr) -> Self {
-                RunnerStruct {
-                    #input,
-                    output: PhantomData,
+        None => {
+            let from_state = if dp.part == Part(2) {
+                quote! {
+                    fn from_state(input: Rc<dyn Any>, _state: Rc<dyn Any>) -> Result<Self, Box<dyn Error>> {
+                        Self::from_input(input)
+                    }
                 }
+            } else {
+                pm2::TokenStream::new()
+            };
+
+            quote! {
+                fn from_input(input: Rc<dyn Any>) -> Result<Self, Box<dyn Error>> {
+                    #check_input
+
+                    Ok( RunnerStruct {
+                        input,
+                        #given_state_init
+                        #params_init
+                    } )
+                }
+
+                #from_state
             }
         }
     };
 
-    let run = if let Some(t) = solver.special_type {
-        let runner = match t {
-            SpecialType::Result => quote! { #fn_runner(self.input.borrow())? },
-            SpecialType::Option => {
-                quote! { #fn_runner(self.input.borrow()).ok_or("runner produce no value")? }
-            }
-        };
+    let state = if solver.both {
+        let both_call = both_call(arg.clone());
+        quote! {
+            fn try_run_both(&self) -> Result<aoc_runner::BothAnswers, Box<dyn Error>> {
+                let (first, second) = #both_call;
+                let first = (&SolverOutput::new(first)).into_answer()?;
+                let second = (&SolverOutput::new(second.expect("part 2 is answered along with part 1"))).into_answer()?;
 
+                Ok((Box::new(first), Box::new(second)))
+            }
+        }
+    } else {
+        let state_call = call(arg.clone());
         quote! {
-            fn run(&self) -> Box<dyn Display> {
-                self.try_run().expect("failed to run")
+            fn try_state(&self) -> Result<Rc<dyn Any>, Box<dyn Error>> {
+                let (output, state) = #state_call;
+                (&SolverOutput::new(output)).into_answer()?;
+
+                state
+                    .map(|state| Rc::new(state) as Rc<dyn Any>)
+                    .ok_or_else(|| "this solution gives no state".into())
             }
+        }
+    };
 
+    let try_run = if gives_state(solver, dp) {
+        let state_call = call(arg.clone());
+        quote! {
             fn try_run(&self) -> Result<Box<dyn Display>, Box<dyn Error>> {
-                Ok( Box::new( #runner ) )
+                let (output, state) = #state_call;
+                let answer = (&SolverOutput::new(output)).into_answer()?;
+
+                // Kept for the part 2 solutions taking it, so that they don't run part 1 again
+                if let Some(state) = state {
+                    *self.given_state.borrow_mut() = Some(Rc::new(state));
+                }
+
+                Ok( Box::new(answer) )
             }
 
-            fn bench(&self, black_box: fn(&dyn Display)) {
-                black_box( &#fn_runner(self.input.borrow()).unwrap() )
+            fn shared_state(&self) -> Option<Rc<dyn Any>> {
+                self.given_state.borrow().clone()
             }
         }
     } else {
         quote! {
-            fn run(&self) -> Box<dyn Display> {
-                Box::new( #fn_runner(self.input.borrow()) )
+            fn try_run(&self) -> Result<Box<dyn Display>, Box<dyn Error>> {
+                Ok( Box::new( #run_answer? ) )
             }
+        }
+    };
+
+    let run = quote! {
+        fn run(&self) -> Box<dyn Display> {
+            self.try_run().expect("failed to run")
+        }
+
+        #try_run
+
+        #shared_state
+
+        fn bench(&self, black_box: fn(&dyn Display)) {
+            black_box( &#run_answer.unwrap() )
+        }
+
+        #state
+    };
+
+    // Benchmarks copy the generator output beforehand, so that the copy is not timed
+    let prepared = match solver.input_kind {
+        InputKind::Ref => pm2::TokenStream::new(),
+        kind => {
+            let (binding, arg, rest) = if kind == InputKind::Mut {
+                (quote! { mut input }, quote_spanned! { span => #input_trait::input_mut(&mut *input) }, quote! { input })
+            } else {
+                (quote! { input }, quote_spanned! { span => #input_trait::into_input(*input) }, quote! { Box::new(()) })
+            };
+            let call = answer(arg.clone());
+
+            let both = if solver.both {
+                let both_call = both_call(arg);
+                quote! {
+                    fn try_run_both_prepared(&self, prepared: Box<dyn Any>) -> Result<(aoc_runner::BothAnswers, Box<dyn Any>), Box<dyn Error>> {
+                        let #binding = #generated
+                            .unprepare(prepared)
+                            .map_err(|_| "input prepared by another runner")?;
+                        let (first, second) = #both_call;
+                        let first = (&SolverOutput::new(first)).into_answer()?;
+                        let second = (&SolverOutput::new(second.expect("part 2 is answered along with part 1"))).into_answer()?;
+
+                        Ok(((Box::new(first), Box::new(second)), #rest))
+                    }
+                }
+            } else {
+                pm2::TokenStream::new()
+            };
+
+            quote! {
+                fn prepare(&self) -> Box<dyn Any> {
+                    Box::new(#copy_input)
+                }
+
+                fn bench_prepared(&self, prepared: Box<dyn Any>, black_box: fn(&dyn Display)) -> Box<dyn Any> {
+                    let #binding = #generated
+                        .unprepare(prepared)
+                        .expect("input prepared by another runner");
+                    black_box( &#call.unwrap() );
+                    #rest
+                }
+
+                fn try_run_prepared(&self, prepared: Box<dyn Any>) -> Result<(aoc_runner::Answer, Box<dyn Any>), Box<dyn Error>> {
+                    let #binding = #generated
+                        .unprepare(prepared)
+                        .map_err(|_| "input prepared by another runner")?;
+                    let answer = #call?;
 
-            fn bench(&self, black_box: fn(&dyn Display)) {
-                black_box( &#fn_runner(self.input.borrow()) )
+                    Ok((Box::new(answer), #rest))
+                }
+
+                #both
             }
         }
     };
 
-    quote! {
+    Ok(quote! {
+        impl RunnerStruct {
+            #constructors
+        }
+
         impl Runner for RunnerStruct {
             #gen
 
             #run
+
+            #prepared
+
+            // Only the output of a generator is worth sharing
+            fn shared_input(&self) -> Option<Rc<dyn Any>> {
+                __AocGenerator::NAME.map(|_| self.input.clone())
+            }
         }
-    }
+    })
 }
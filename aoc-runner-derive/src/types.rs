@@ -1,80 +1,49 @@
 use proc_macro as pm;
-use proc_macro2 as pm2;
-use quote::quote;
 
-#[derive(Clone, Debug, Default)]
-pub(crate) struct Runner {
-    pub generator: Option<Generator>,
-    pub solver: Option<Solver>,
+/// How a solver takes the output of its generator
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) enum InputKind {
+    /// `&T`, borrowed from the generator output
+    Ref,
+    /// `&mut T`, borrowed from a copy of the generator output
+    Mut,
+    /// `T`, a copy of the generator output
+    Owned,
 }
 
-impl Runner {
-    pub fn with_generator(&mut self, generator: Generator) {
-        if self.solver.is_some() {
-            panic!("Generators must be defined before solutions: {:?}", self);
-        }
-        if self.generator.is_some() {
-            panic!("A generator is already defined: {:?}", self);
-        }
-        self.generator = Some(generator)
-    }
-
-    pub fn with_solver(&mut self, solver: Solver) {
-        if self.solver.is_some() {
-            panic!("A solution is already defined: {:?}", self);
-        }
-        self.solver = Some(solver)
-    }
-}
-
-#[derive(Copy, Clone, Debug)]
-pub(crate) enum SpecialType {
-    Result,
-    Option,
+/// A constant of a solver, given as `name = value` in `params(...)`, that can be overridden at runtime
+pub(crate) struct Param {
+    pub name: syn::Ident,
+    pub default: syn::Expr,
 }
 
-#[derive(Clone, Debug)]
-pub(crate) struct Generator {
-    name: String,
-    out_t: String,
-    pub special_type: Option<SpecialType>,
-}
-
-impl Generator {
-    pub fn new(
-        name: &syn::Ident,
-        out_t: &syn::Type,
-        special_type: Option<SpecialType>,
-    ) -> Generator {
-        Generator {
-            name: name.to_string(),
-            out_t: quote! { #out_t }.to_string(),
-            special_type,
-        }
-    }
-
-    pub fn get_name(&self) -> syn::Ident {
-        syn::Ident::new(&self.name, pm::Span::call_site().into())
-    }
+impl syn::parse::Parse for Param {
+    fn parse(input: syn::parse::ParseStream) -> syn::Result<Param> {
+        let name = input.parse()?;
+        input.parse::<syn::Token![=]>()?;
+        let default = input.parse()?;
 
-    pub fn get_out_t(&self) -> pm2::TokenStream {
-        self.out_t.parse().expect("failed to parse generator type")
+        Ok(Param { name, default })
     }
 }
 
 #[derive(Clone, Debug)]
 pub(crate) struct Solver {
     name: String,
-    //    out_t: String,
-    pub special_type: Option<SpecialType>,
+    pub input_kind: InputKind,
+    /// Solves both parts at once, returning `(part1, part2)`
+    pub both: bool,
+    /// Where the errors about the solution point : the name of the solver
+    pub span: proc_macro2::Span,
 }
 
 impl Solver {
-    pub fn new(name: &syn::Ident, _out_t: &syn::Type, special_type: Option<SpecialType>) -> Solver {
+    pub fn new(name: &syn::Ident, input_kind: InputKind, both: bool) -> Solver {
         Solver {
             name: name.to_string(),
-            //            out_t: quote! { #out_t }.to_string(),
-            special_type,
+            input_kind,
+            both,
+            span: name.span(),
         }
     }
 
@@ -1,10 +1,44 @@
-use crate::types::SpecialType;
-use aoc_runner_internal::{Day, DayPart};
+use crate::types::{InputKind, Param};
+use aoc_runner_internal::{Day, DayPart, Part};
 use proc_macro as pm;
+use syn::parse::Parser;
+use syn::punctuated::Punctuated;
+
+/// Takes the `params(name = value, ...)` of a solver out of its attribute arguments
+pub(crate) fn extract_params(args: pm::TokenStream) -> syn::Result<(pm::TokenStream, Vec<Param>)> {
+    let mut rest = Vec::new();
+    let mut params = Vec::new();
+    let mut tokens = args.into_iter().peekable();
+
+    while let Some(token) = tokens.next() {
+        if let pm::TokenTree::Ident(ident) = &token {
+            if ident.to_string() == "params" {
+                if let Some(pm::TokenTree::Group(group)) = tokens.peek() {
+                    if group.delimiter() == pm::Delimiter::Parenthesis {
+                        let parser = Punctuated::<Param, syn::Token![,]>::parse_terminated;
+                        let parsed = parser.parse(group.stream()).map_err(|e| {
+                            syn::Error::new(
+                                e.span(),
+                                format!("{}, params must be given as `params(name = value, ...)`", e),
+                            )
+                        })?;
+                        params.extend(parsed);
+                        tokens.next();
+                        continue;
+                    }
+                }
+            }
+        }
+
+        rest.push(token);
+    }
+
+    Ok((rest.into_iter().collect(), params))
+}
 
 pub(crate) fn extract_meta(
     args: pm::TokenStream,
-) -> (syn::Ident, Option<syn::Ident>, Option<syn::Ident>) {
+) -> syn::Result<(syn::Ident, Option<syn::Ident>, Option<syn::Ident>)> {
     let mut idents = args.into_iter().filter_map(|a| {
         if let pm::TokenTree::Ident(_) = a {
             Some(a.into())
@@ -13,41 +47,35 @@ pub(crate) fn extract_meta(
         }
     });
 
-    let day: pm::TokenStream = idents.next().expect("Couldn't find day");
-    let day: syn::Ident = syn::parse(day).expect("failed to parse day");
+    let day: pm::TokenStream = idents.next().ok_or_else(|| {
+        syn::Error::new(pm::Span::call_site().into(), "expected a day, like `day1`")
+    })?;
+    let day: syn::Ident = syn::parse(day)?;
 
     let part = idents.next().and_then(|i| syn::parse(i).ok());
     let name = idents.next().and_then(|i| syn::parse(i).ok());
 
-    (day, part, name)
+    Ok((day, part, name))
 }
 
-pub(crate) fn extract_result(ty: &syn::Type) -> Option<(SpecialType, syn::Type)> {
-    use syn::*;
-
-    if let Type::Path(TypePath {
-        path: Path { segments: s, .. },
-        ..
-    }) = ty
-    {
-        if let Some(p) = s.last() {
-            if p.ident == "Result" {
-                if let PathArguments::AngleBracketed(a) = &p.arguments {
-                    if let Some(GenericArgument::Type(t)) = a.args.first() {
-                        return Some((SpecialType::Result, t.clone()));
-                    }
-                }
-            } else if p.ident == "Option" {
-                if let PathArguments::AngleBracketed(a) = &p.arguments {
-                    if let Some(GenericArgument::Type(t)) = a.args.first() {
-                        return Some((SpecialType::Option, t.clone()));
-                    }
-                }
-            }
-        }
-    }
+pub(crate) fn parse_day(day: &syn::Ident) -> syn::Result<Day> {
+    day.to_string()
+        .parse()
+        .map_err(|e| syn::Error::new(day.span(), format!("{}, expected a day like `day1`", e)))
+}
 
-    None
+pub(crate) fn parse_part(part: &syn::Ident) -> syn::Result<Part> {
+    part.to_string().parse().map_err(|e| {
+        syn::Error::new(part.span(), format!("{}, expected `part1` or `part2`", e))
+    })
+}
+
+pub(crate) fn extract_input_kind(ty: &syn::Type) -> InputKind {
+    match ty {
+        syn::Type::Reference(r) if r.mutability.is_some() => InputKind::Mut,
+        syn::Type::Reference(_) => InputKind::Ref,
+        _ => InputKind::Owned,
+    }
 }
 
 pub(crate) fn to_snakecase(dp: &DayPart) -> syn::Ident {
@@ -61,6 +89,22 @@ pub(crate) fn to_snakecase(dp: &DayPart) -> syn::Ident {
     syn::Ident::new(&name, pm::Span::call_site().into())
 }
 
+/// Name of the `Factory` method building a runner from the output of a shared generator
+pub(crate) fn to_shared(dp: &DayPart) -> syn::Ident {
+    syn::Ident::new(
+        &format!("shared_{}", to_snakecase(dp)),
+        pm::Span::call_site().into(),
+    )
+}
+
+/// Name of the `Factory` method building a part 2 runner from the state given by part 1
+pub(crate) fn to_stated(dp: &DayPart) -> syn::Ident {
+    syn::Ident::new(
+        &format!("stated_{}", to_snakecase(dp)),
+        pm::Span::call_site().into(),
+    )
+}
+
 pub(crate) fn to_camelcase(dp: &DayPart) -> syn::Ident {
     let DayPart { day, part, name } = dp;
 
@@ -73,8 +117,9 @@ pub(crate) fn to_camelcase(dp: &DayPart) -> syn::Ident {
     syn::Ident::new(&name, pm::Span::call_site().into())
 }
 
-pub(crate) fn to_input(d: Day) -> syn::Ident {
-    syn::Ident::new(&format!("input_day{}", d.0), pm::Span::call_site().into())
+/// Name of the type implementing `aoc_runner::Generator` for a solution, in the module of its generator
+pub(crate) fn to_generator(dp: &DayPart, span: proc_macro2::Span) -> syn::Ident {
+    syn::Ident::new(&format!("__AocGenerator{}", to_camelcase(dp)), span)
 }
 
 pub(crate) fn is_rls() -> bool {
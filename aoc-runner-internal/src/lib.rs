@@ -1,11 +1,8 @@
 extern crate serde;
 extern crate serde_derive;
-extern crate serde_json;
 
 use serde_derive::*;
 use std::cmp::Ordering;
-use std::error;
-use std::fs;
 use std::iter::FromIterator;
 use std::ops::Deref;
 use std::ops::DerefMut;
@@ -88,61 +85,51 @@ impl Ord for DayPart {
 pub struct DayParts {
     pub year: u32,
     parts: Vec<DayPart>,
+    /// Generator function used by each solution that has one
+    #[serde(default)]
+    generators: Vec<(DayPart, GeneratorFn)>,
+    /// Solutions solving both parts in a single call, by their part 1
+    #[serde(default)]
+    both: Vec<DayPart>,
 }
 
 impl DayParts {
-    pub fn save(&self) -> Result<(), Box<dyn error::Error>> {
-        let mut target_directory: std::path::PathBuf = "target".into();
-        let proc_dir_path: std::path::PathBuf = env!("PROC_OUT_DIR").into();
-        for potential_path in proc_dir_path.ancestors() {
-            // There isn't a good way to get the target root dir from a proc-macro.
-            // But a build script gets its own OUT_DIR in the target dir, so pass that
-            // so the proc-macro can see it and search upwards from there.
-            // This is a hack, but so is the whole saving-and-loading-during-build thing.
-            if potential_path.file_name() == Some("target".as_ref()) {
-                target_directory = potential_path.into();
-                break;
-            }
-        }
-        if !target_directory.join("aoc").exists() {
-            fs::create_dir(target_directory.join("aoc"))?;
-        }
-        let crate_name = std::env::var("CARGO_CRATE_NAME").expect("Cannot determine crate name");
-        let f =
-            fs::File::create(target_directory.join(format!("aoc/completed_{}.json", crate_name)))?;
-        serde_json::to_writer_pretty(f, &self)?;
-
-        // Still write to old filename, in case our versions are mismatched.
-        let f = fs::File::create(target_directory.join("aoc/completed.json"))?;
-
-        serde_json::to_writer_pretty(f, &self)?;
+    pub fn with_generators(mut self, generators: Vec<(DayPart, GeneratorFn)>) -> DayParts {
+        self.generators = generators;
+        self
+    }
 
-        Ok(())
+    pub fn with_both(mut self, both: Vec<DayPart>) -> DayParts {
+        self.both = both;
+        self
     }
 
-    pub fn load(
-        crate_name: String,
-        root_dir: Option<std::path::PathBuf>,
-    ) -> Result<Self, Box<dyn error::Error>> {
-        let root_dir = root_dir.unwrap_or_else(|| "target".into());
-        let mut path = root_dir
-            .join("aoc")
-            .join(format!("completed_{}.json", crate_name));
-        let mut opt_f = fs::File::open(&path);
-
-        if let Err(err) = &opt_f {
-            if err.kind() == std::io::ErrorKind::NotFound {
-                // If missing, get old filename, in case our versions are mismatched
-                path.pop();
-                path.push("completed.json");
-                opt_f = fs::File::open(path);
-            }
-        }
+    /// Checks whether a solution is solved along with the other part, by a single call
+    pub fn solves_both(&self, dp: &DayPart) -> bool {
+        self.both
+            .iter()
+            .any(|d| d.day == dp.day && d.name == dp.name)
+    }
 
-        Ok(serde_json::from_reader(opt_f?)?)
+    /// Gets the generator function used by a solution.
+    ///
+    /// Solutions sharing a generator get the same path.
+    pub fn generator(&self, dp: &DayPart) -> Option<&GeneratorFn> {
+        self.generators
+            .iter()
+            .find(|(d, _)| d == dp)
+            .map(|(_, generator)| generator)
     }
 }
 
+/// A generator function, used by one or more solutions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GeneratorFn {
+    pub name: String,
+    /// Full path of the function, telling it apart from generators of the same name in other modules
+    pub path: String,
+}
+
 impl Deref for DayParts {
     type Target = [DayPart];
 
@@ -166,6 +153,8 @@ impl DayPartsBuilder {
         DayParts {
             year,
             parts: self.parts,
+            generators: Vec::new(),
+            both: Vec::new(),
         }
     }
 }
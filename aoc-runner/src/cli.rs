@@ -0,0 +1,481 @@
+use crate::registry::{input_path, registered, Solution};
+use crate::{ArcStr, Runner, Stats, PARAMS_ENV};
+use serde_json::json;
+use std::any::Any;
+use std::collections::HashMap;
+use std::env;
+use std::error::Error;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Read};
+use std::ops::RangeInclusive;
+use std::path::Path;
+use std::process;
+use std::rc::Rc;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// Untimed runs before timing a solution many times, unless `--warm-up` says otherwise
+const DEFAULT_WARM_UP: u32 = 10;
+
+fn usage(year: u32) -> String {
+    let program = env::args()
+        .next()
+        .map(|program| match Path::new(&program).file_name() {
+            Some(name) => name.to_string_lossy().into_owned(),
+            None => program,
+        })
+        .unwrap_or_else(|| "aoc".to_string());
+
+    format!(
+        "\
+Usage: {program} [OPTIONS]
+
+Runs your solutions, over input/{year}/dayN.txt (relative to the current directory) by default.
+
+Options:
+    -d, --day DAY           Only run the solutions of this day
+    -p, --part PART         Only run the solutions of this part
+    -n, --name NAME         Only run the solutions with this name, `default` for the unnamed ones
+    -i, --input FILE        Use an alternate input file, or `-` for stdin. Can be repeated.
+        --param NAME=VALUE  Override a param of the solutions. Can be repeated.
+    -r, --repeat N          Time each solution over this many runs, and print statistics
+        --warm-up N         Number of untimed runs before timing, with --repeat. Defaults to {warm_up}.
+        --format FORMAT     `text` (default), or `json` for one object per answer and line
+    -h, --help              Print this help",
+        program = program,
+        year = year,
+        warm_up = DEFAULT_WARM_UP
+    )
+}
+
+/// How the results are printed
+#[derive(Copy, Clone, PartialEq)]
+enum Format {
+    Text,
+    Json,
+}
+
+/// The command line of the binaries generated by `aoc_main!`, close to the one of `cargo aoc`
+struct Options {
+    day: Option<u8>,
+    part: Option<u8>,
+    name: Option<String>,
+    inputs: Vec<String>,
+    params: Vec<String>,
+    repeat: Option<u32>,
+    warm_up: Option<u32>,
+    format: Format,
+}
+
+impl Options {
+    /// Parses the arguments, `None` when the help is asked for
+    fn parse<I: Iterator<Item = String>>(mut args: I) -> Result<Option<Options>, String> {
+        let mut options = Options {
+            day: None,
+            part: None,
+            name: None,
+            inputs: Vec::new(),
+            params: Vec::new(),
+            repeat: None,
+            warm_up: None,
+            format: Format::Text,
+        };
+
+        while let Some(arg) = args.next() {
+            // `--day=5` is the same as `--day 5`
+            let (flag, inline) = match arg.find('=') {
+                Some(i) if arg.starts_with("--") => (&arg[..i], Some(arg[i + 1..].to_string())),
+                _ => (arg.as_str(), None),
+            };
+            let mut value = || {
+                inline
+                    .clone()
+                    .or_else(|| args.next())
+                    .ok_or_else(|| format!("Missing value for `{}`", flag))
+            };
+
+            match flag {
+                "-h" | "--help" => return Ok(None),
+                "-d" | "--day" => options.day = Some(parse_number(&value()?, "day", 1..=25)?),
+                "-p" | "--part" => options.part = Some(parse_number(&value()?, "part", 1..=2)?),
+                "-n" | "--name" => options.name = Some(value()?),
+                "-i" | "--input" => options.inputs.push(value()?),
+                "--param" => options.params.push(value()?),
+                "-r" | "--repeat" => {
+                    options.repeat = Some(parse_number(&value()?, "repeat count", 1..=u32::MAX)?)
+                }
+                "--warm-up" => {
+                    options.warm_up = Some(parse_number(&value()?, "warm-up count", 0..=u32::MAX)?)
+                }
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "text" => Format::Text,
+                        "json" => Format::Json,
+                        format => return Err(format!("Unknown format `{}`, expected `text` or `json`", format)),
+                    }
+                }
+                _ => return Err(format!("Unexpected argument `{}`", arg)),
+            }
+        }
+
+        if options.warm_up.is_some() && options.repeat.is_none() {
+            return Err("`--warm-up` can only be used with `--repeat`".into());
+        }
+
+        Ok(Some(options))
+    }
+}
+
+/// Parses a number, also given as `day5` or `part2`
+fn parse_number<T: FromStr + PartialOrd>(value: &str, what: &str, range: RangeInclusive<T>) -> Result<T, String> {
+    match value.trim_start_matches(char::is_alphabetic).parse() {
+        Ok(n) if range.contains(&n) => Ok(n),
+        _ => Err(format!("Invalid {} `{}`", what, value)),
+    }
+}
+
+fn matches_name(name: Option<&str>, wanted: &str) -> bool {
+    match name {
+        Some(name) => name.eq_ignore_ascii_case(wanted),
+        None => wanted.eq_ignore_ascii_case("default"),
+    }
+}
+
+enum Timings {
+    /// A single run, without generator timing when its output was shared
+    Once { generator: Option<Duration>, runner: Duration },
+    Repeated { generator: Stats, runner: Stats },
+}
+
+/// The answers of a solution (of both parts, for a solution of both parts), or what failed
+type Outcome = Result<(Vec<String>, Timings), (&'static str, Box<dyn Error>)>;
+
+/// Parses the command line, then runs the selected solutions, for `aoc_main!`.
+///
+/// Default inputs are read from `input/{year}/dayN.txt` in the current directory, so that the binary can be
+/// run anywhere. Exits with an error code when a solution fails.
+#[doc(hidden)]
+pub fn main(year: u32) {
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", usage(year));
+            return;
+        }
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, usage(year));
+            process::exit(2);
+        }
+    };
+
+    match run(year, &options) {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+    }
+}
+
+/// Runs the selected solutions, telling whether they all succeeded
+fn run(year: u32, options: &Options) -> Result<bool, Box<dyn Error>> {
+    if !options.params.is_empty() {
+        // Takes precedence over the params already in the environment
+        let mut params = options.params.clone();
+        params.extend(env::var(PARAMS_ENV).ok());
+        env::set_var(PARAMS_ENV, params.join(","));
+    }
+
+    // `Option::is_none_or` needs Rust 1.82
+    #[allow(clippy::unnecessary_map_or)]
+    let selected: Vec<&Solution> = registered()
+        .into_iter()
+        .filter(|s| s.year == year)
+        .filter(|s| options.day.map_or(true, |d| s.day == d))
+        .filter(|s| options.part.map_or(true, |p| s.part == p))
+        .filter(|s| options.name.as_deref().map_or(true, |n| matches_name(s.name, n)))
+        .collect();
+
+    if selected.is_empty() {
+        return Err(format!("No matching solution of {} found", year).into());
+    }
+
+    let mut days: Vec<u8> = selected.iter().map(|s| s.day).collect();
+    days.dedup();
+
+    let alternate = read_inputs(&options.inputs)?;
+    if !alternate.is_empty() && days.len() > 1 {
+        return Err("Alternate inputs can only be used for a single day, pick it with `-d`".into());
+    }
+
+    if options.format == Format::Text {
+        println!("Advent of code {}", year);
+        if let Some(repeat) = options.repeat {
+            let warm_up = options.warm_up.unwrap_or(DEFAULT_WARM_UP);
+            println!("Timing {} runs, after {} warm-up runs\n", repeat, warm_up);
+        }
+    }
+
+    let mut succeeded = true;
+    for day in days {
+        let solutions: Vec<&Solution> = selected.iter().copied().filter(|s| s.day == day).collect();
+
+        let inputs = if alternate.is_empty() {
+            let path = input_path(".", year, day);
+
+            match fs::read_to_string(&path) {
+                Ok(input) => vec![(path, ArcStr::from(&input))],
+                Err(_) => {
+                    let label = Some(path.as_str()).filter(|_| options.format == Format::Json);
+                    for solution in solutions {
+                        let failure = format!("{} not found", path).into();
+                        report(year, options.format, solution, label, &Err(("reading input", failure)));
+                    }
+                    succeeded = false;
+                    continue;
+                }
+            }
+        } else {
+            alternate.clone()
+        };
+
+        for (name, input) in &inputs {
+            let label = if inputs.len() > 1 || options.format == Format::Json {
+                Some(name.as_str())
+            } else {
+                None
+            };
+
+            // Outputs of the generators, by generator, shared by the solutions using them
+            let mut shared_inputs = HashMap::new();
+            // State given by part 1 when it ran, for the part 2 solutions taking it
+            let mut part1_state = None;
+
+            for &solution in &solutions {
+                // A solution of both parts is run once, along with part 1, when both parts are selected
+                let both = solution.both
+                    && solutions
+                        .iter()
+                        .any(|s| s.both && s.name == solution.name && s.part != solution.part);
+                if both && solution.part == 2 {
+                    continue;
+                }
+
+                let outcome = match options.repeat {
+                    Some(repeat) => {
+                        let warm_up = options.warm_up.unwrap_or(DEFAULT_WARM_UP);
+                        run_repeated(solution, both, input, repeat, warm_up)
+                    }
+                    None => run_once(solution, both, input, &mut shared_inputs, &mut part1_state),
+                };
+
+                succeeded &= outcome.is_ok();
+                report(year, options.format, solution, label, &outcome);
+            }
+        }
+    }
+
+    Ok(succeeded)
+}
+
+/// Reads the alternate inputs given with `-i`, `-` reading stdin
+fn read_inputs(paths: &[String]) -> Result<Vec<(String, ArcStr)>, Box<dyn Error>> {
+    let mut inputs: Vec<(String, ArcStr)> = Vec::new();
+
+    for path in paths {
+        let (name, input) = if path == "-" {
+            if inputs.iter().any(|(name, _)| name == "stdin") {
+                return Err("stdin can only be used once as an input".into());
+            }
+
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input)?;
+            ("stdin".to_string(), input)
+        } else {
+            let input = fs::read_to_string(path).map_err(|e| format!("Failed to read {} : {}", path, e))?;
+            (path.clone(), input)
+        };
+
+        inputs.push((name, ArcStr::from(&input)));
+    }
+
+    Ok(inputs)
+}
+
+fn answers(runner: &dyn Runner, both: bool) -> Result<Vec<String>, Box<dyn Error>> {
+    if both {
+        let (first, second) = runner.try_run_both()?;
+        Ok(vec![first.to_string(), second.to_string()])
+    } else {
+        Ok(vec![runner.try_run()?.to_string()])
+    }
+}
+
+fn run_once(
+    solution: &Solution,
+    both: bool,
+    input: &ArcStr,
+    shared_inputs: &mut HashMap<&'static str, Rc<dyn Any>>,
+    part1_state: &mut Option<Rc<dyn Any>>,
+) -> Outcome {
+    let start_time = Instant::now();
+
+    // Solutions using the same generator as a previous one reuse its output,
+    // and part 2 reuses the state part 1 gave when it ran instead of running it again
+    let shared = solution.generator_path.and_then(|g| shared_inputs.get(g).cloned());
+    let runner = match (solution.state_runner, &*part1_state, &shared) {
+        (Some(stated_runner), Some(state), _) => stated_runner(input.clone(), shared.clone(), state.clone()),
+        (_, _, Some(shared)) => (solution.shared_runner)(shared.clone()),
+        (_, _, None) => (solution.runner)(input.clone()),
+    }
+    .map_err(|e| ("generating", e))?;
+    let inter_time = Instant::now();
+
+    if let (Some(generator), Some(input)) = (solution.generator_path, runner.shared_input()) {
+        shared_inputs.entry(generator).or_insert(input);
+    }
+
+    let answers = answers(&*runner, both).map_err(|e| ("running", e))?;
+    let final_time = Instant::now();
+
+    if let Some(state) = runner.shared_state() {
+        *part1_state = Some(state);
+    }
+
+    let timings = Timings::Once {
+        generator: shared.map_or(Some(inter_time - start_time), |_| None),
+        runner: final_time - inter_time,
+    };
+
+    Ok((answers, timings))
+}
+
+/// Each run gets its own generator output, and is timed on its own
+fn run_repeated(solution: &Solution, both: bool, input: &ArcStr, repeat: u32, warm_up: u32) -> Outcome {
+    let mut generator = Vec::new();
+    let mut runner = Vec::new();
+    let mut last = Vec::new();
+
+    for i in 0..warm_up + repeat {
+        let start_time = Instant::now();
+        let built = (solution.runner)(input.clone()).map_err(|e| ("generating", e))?;
+        let inter_time = Instant::now();
+
+        // The input is copied beforehand for solvers that take it by value, and dropped afterwards
+        let prepared = built.prepare();
+        let run_time = Instant::now();
+        let answers = if both {
+            built
+                .try_run_both_prepared(prepared)
+                .map(|((first, second), rest)| (vec![first.to_string(), second.to_string()], rest))
+        } else {
+            built
+                .try_run_prepared(prepared)
+                .map(|(answer, rest)| (vec![answer.to_string()], rest))
+        };
+        let final_time = Instant::now();
+        last = answers.map_err(|e| ("running", e))?.0;
+
+        if i >= warm_up {
+            generator.push(inter_time - start_time);
+            runner.push(final_time - run_time);
+        }
+    }
+
+    let timings = Timings::Repeated {
+        generator: Stats::of(&generator),
+        runner: Stats::of(&runner),
+    };
+
+    Ok((last, timings))
+}
+
+fn report(year: u32, format: Format, solution: &Solution, input: Option<&str>, outcome: &Outcome) {
+    match format {
+        Format::Text => report_text(solution, input, outcome),
+        Format::Json => report_json(year, solution, input, outcome),
+    }
+}
+
+fn report_text(solution: &Solution, input: Option<&str>, outcome: &Outcome) {
+    let title = |part: u8| match input {
+        Some(input) => format!("{} [{}]", solution.title(part), input),
+        None => solution.title(part),
+    };
+
+    match outcome {
+        Ok((answers, timings)) => {
+            let mut report = String::new();
+            for (part, answer) in (solution.part..).zip(answers) {
+                let _ = writeln!(report, "{}: {}", title(part), answer);
+            }
+
+            let _ = match timings {
+                Timings::Once { generator, runner } => {
+                    let generator = generator.map_or_else(|| "shared".to_string(), |g| format!("{:?}", g));
+                    write!(report, "\tgenerator: {},\n\trunner: {:?}\n", generator, runner)
+                }
+                Timings::Repeated { generator, runner } => {
+                    write!(report, "\tgenerator: {},\n\trunner: {}\n", generator, runner)
+                }
+            };
+
+            println!("{}", report);
+        }
+        Err((step, e)) => eprintln!("{}: FAILED while {}:\n{:#?}\n", title(solution.part), step, e),
+    }
+}
+
+/// One object per line, for each answer (two for a solution of both parts) or failure
+fn report_json(year: u32, solution: &Solution, input: Option<&str>, outcome: &Outcome) {
+    let report = |part: u8, fields: serde_json::Value| {
+        let mut report = json!({
+            "year": year,
+            "day": solution.day,
+            "part": part,
+            "name": solution.name,
+            "input": input,
+        });
+        if let (Some(report), serde_json::Value::Object(fields)) = (report.as_object_mut(), fields) {
+            report.extend(fields);
+        }
+
+        println!("{}", report);
+    };
+
+    match outcome {
+        Ok((answers, timings)) => {
+            let timings = match timings {
+                Timings::Once { generator, runner } => json!({
+                    "generator_ns": generator.map(|g| g.as_nanos() as u64),
+                    "runner_ns": runner.as_nanos() as u64,
+                }),
+                Timings::Repeated { generator, runner } => json!({
+                    "generator": stats_json(generator),
+                    "runner": stats_json(runner),
+                }),
+            };
+
+            for (part, answer) in (solution.part..).zip(answers) {
+                let mut fields = timings.clone();
+                fields["answer"] = json!(answer);
+                report(part, fields);
+            }
+        }
+        Err((step, e)) => report(
+            solution.part,
+            json!({ "error": format!("FAILED while {}: {}", step, e) }),
+        ),
+    }
+}
+
+fn stats_json(stats: &Stats) -> serde_json::Value {
+    json!({
+        "min_ns": stats.min.as_nanos() as u64,
+        "median_ns": stats.median.as_nanos() as u64,
+        "mean_ns": stats.mean.as_nanos() as u64,
+        "stddev_ns": stats.stddev.as_nanos() as u64,
+    })
+}
@@ -1,8 +1,29 @@
-use std::borrow::Borrow;
+use std::any::Any;
+use std::borrow::{Borrow, BorrowMut};
+use std::cell::Cell;
+use std::env;
 use std::error::Error;
 use std::fmt::Display;
+use std::marker::PhantomData;
+use std::rc::Rc;
+use std::str::FromStr;
 use std::sync::Arc;
 
+mod cli;
+mod registry;
+mod stats;
+
+pub use crate::registry::{no_generator, Generator};
+pub use crate::registry::{run, solutions, SolutionInfo};
+#[doc(hidden)]
+pub use crate::cli::main;
+#[doc(hidden)]
+pub use crate::registry::{registered, test_answer, Solution};
+#[doc(hidden)]
+pub use crate::stats::Stats;
+#[doc(hidden)]
+pub use inventory;
+
 #[inline]
 pub fn identity<T>(t: T) -> T {
     t
@@ -36,6 +57,408 @@ impl Borrow<Arc<str>> for ArcStr {
     }
 }
 
+/// Environment variable overriding the params of the solutions, as `name=value` pairs separated by commas
+pub const PARAMS_ENV: &str = "AOC_PARAMS";
+
+/// Reads the value of a param in the `AOC_PARAMS` environment variable, if it is overridden there
+#[doc(hidden)]
+pub fn param<T: FromStr>(name: &str) -> Result<Option<T>, Box<dyn Error>>
+where
+    T::Err: Display,
+{
+    let params = match env::var(PARAMS_ENV) {
+        Ok(params) => params,
+        Err(_) => return Ok(None),
+    };
+
+    for param in params.split(',') {
+        let mut kv = param.splitn(2, '=');
+        if kv.next().map(str::trim) != Some(name) {
+            continue;
+        }
+
+        let value = kv.next().unwrap_or("").trim();
+        return value
+            .parse()
+            .map(Some)
+            .map_err(|e| format!("Invalid value `{}` for param `{}` : {}", value, name, e).into());
+    }
+
+    Ok(None)
+}
+
+/// Looks up the answer of a day & part in an `answers/{year}.toml` file, as written by `cargo aoc` :
+/// `[dayN]` tables, holding `partM = "answer"` (or integer) keys.
+#[doc(hidden)]
+pub fn known_answer(answers: &toml::Value, day: u8, part: u8) -> Option<String> {
+    match answers.get(format!("day{}", day))?.get(format!("part{}", part))? {
+        toml::Value::String(answer) => Some(answer.clone()),
+        toml::Value::Integer(answer) => Some(answer.to_string()),
+        _ => None,
+    }
+}
+
+/// The result of a solution that can fail, turned into the answer to display.
+///
+/// It is implemented for `Option` & `Result` (including aliases, like `anyhow::Result`), and can be implemented
+/// for your own types. Solutions returning any other type implementing `Display` don't need it.
+pub trait IntoAnswer {
+    type Answer: Display + 'static;
+
+    fn into_answer(self) -> Result<Self::Answer, Box<dyn Error>>;
+}
+
+impl<T: Display + 'static> IntoAnswer for Option<T> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Box<dyn Error>> {
+        self.ok_or_else(|| "runner produce no value".into())
+    }
+}
+
+impl<T: Display + 'static, E: Into<Box<dyn Error>>> IntoAnswer for Result<T, E> {
+    type Answer = T;
+
+    fn into_answer(self) -> Result<T, Box<dyn Error>> {
+        self.map_err(Into::into)
+    }
+}
+
+/// Wraps the output of a solution, so that calling `(&SolverOutput::new(output)).into_answer()` picks
+/// `IntoAnswer` when the output implements it, and `Display` otherwise (including `impl Display`).
+#[doc(hidden)]
+pub struct SolverOutput<T>(Cell<Option<T>>);
+
+impl<T> SolverOutput<T> {
+    #[inline]
+    pub fn new(output: T) -> SolverOutput<T> {
+        SolverOutput(Cell::new(Some(output)))
+    }
+
+    #[inline]
+    fn take(&self) -> T {
+        self.0.take().expect("solver output already taken")
+    }
+}
+
+#[doc(hidden)]
+pub trait FallibleOutput {
+    type Answer: Display + 'static;
+
+    fn into_answer(self) -> Result<Self::Answer, Box<dyn Error>>;
+}
+
+impl<T: IntoAnswer> FallibleOutput for &SolverOutput<T> {
+    type Answer = T::Answer;
+
+    #[inline]
+    fn into_answer(self) -> Result<T::Answer, Box<dyn Error>> {
+        self.take().into_answer()
+    }
+}
+
+#[doc(hidden)]
+pub trait DisplayOutput {
+    type Answer: Display + 'static;
+
+    fn into_answer(self) -> Result<Self::Answer, Box<dyn Error>>;
+}
+
+impl<T: Display + 'static> DisplayOutput for &&SolverOutput<T> {
+    type Answer = T;
+
+    #[inline]
+    fn into_answer(self) -> Result<T, Box<dyn Error>> {
+        Ok(self.take())
+    }
+}
+
+/// The answer of a solution, to display
+pub type Answer = Box<dyn Display>;
+
+/// The answers of part 1 & part 2, given by a solution of both parts
+pub type BothAnswers = (Answer, Answer);
+
+/// Splits the output of a part 1 solution returning `(answer, state)`, to give the state to part 2.
+///
+/// Picked over `StatelessOutput` the same way as `FallibleOutput` over `DisplayOutput`.
+#[doc(hidden)]
+pub trait StatefulOutput {
+    type Output;
+    type State: 'static;
+
+    fn split_state(self) -> (Self::Output, Option<Self::State>);
+}
+
+impl<A, S: 'static> StatefulOutput for &SolverOutput<(A, S)> {
+    type Output = A;
+    type State = S;
+
+    #[inline]
+    fn split_state(self) -> (A, Option<S>) {
+        let (answer, state) = self.take();
+        (answer, Some(state))
+    }
+}
+
+impl<A, S: 'static> StatefulOutput for &SolverOutput<Option<(A, S)>> {
+    type Output = Option<A>;
+    type State = S;
+
+    #[inline]
+    fn split_state(self) -> (Option<A>, Option<S>) {
+        match self.take() {
+            Some((answer, state)) => (Some(answer), Some(state)),
+            None => (None, None),
+        }
+    }
+}
+
+impl<A, S: 'static, E> StatefulOutput for &SolverOutput<Result<(A, S), E>> {
+    type Output = Result<A, E>;
+    type State = S;
+
+    #[inline]
+    fn split_state(self) -> (Result<A, E>, Option<S>) {
+        match self.take() {
+            Ok((answer, state)) => (Ok(answer), Some(state)),
+            Err(e) => (Err(e), None),
+        }
+    }
+}
+
+#[doc(hidden)]
+pub trait StatelessOutput {
+    type Output;
+    type State: 'static;
+
+    fn split_state(self) -> (Self::Output, Option<Self::State>);
+}
+
+impl<T> StatelessOutput for &&SolverOutput<T> {
+    type Output = T;
+    type State = ();
+
+    #[inline]
+    fn split_state(self) -> (T, Option<()>) {
+        (self.take(), None)
+    }
+}
+
+/// The output of a generator that can fail, turned into the input given to the solutions.
+///
+/// It is implemented for `Option` & `Result` (including aliases, like `anyhow::Result`), and can be implemented
+/// for your own types. The output of other generators is given as is to the solutions.
+pub trait IntoGenerated {
+    type Generated: 'static;
+
+    fn into_generated(self) -> Result<Self::Generated, Box<dyn Error>>;
+}
+
+impl<T: 'static> IntoGenerated for Option<T> {
+    type Generated = T;
+
+    fn into_generated(self) -> Result<T, Box<dyn Error>> {
+        self.ok_or_else(|| "generator produce no value".into())
+    }
+}
+
+impl<T: 'static, E: Into<Box<dyn Error>>> IntoGenerated for Result<T, E> {
+    type Generated = T;
+
+    fn into_generated(self) -> Result<T, Box<dyn Error>> {
+        self.map_err(Into::into)
+    }
+}
+
+/// Wraps the output of a generator, so that calling `(&GeneratorOutput::new(output)).into_generated()` picks
+/// `IntoGenerated` when the output implements it, and gives the output as is otherwise.
+#[doc(hidden)]
+pub struct GeneratorOutput<T>(Cell<Option<T>>);
+
+impl<T> GeneratorOutput<T> {
+    #[inline]
+    pub fn new(output: T) -> GeneratorOutput<T> {
+        GeneratorOutput(Cell::new(Some(output)))
+    }
+
+    #[inline]
+    fn take(&self) -> T {
+        self.0.take().expect("generator output already taken")
+    }
+}
+
+#[doc(hidden)]
+pub trait FallibleGenerated {
+    type Generated: 'static;
+
+    fn into_generated(self) -> Result<Self::Generated, Box<dyn Error>>;
+}
+
+impl<T: IntoGenerated> FallibleGenerated for &GeneratorOutput<T> {
+    type Generated = T::Generated;
+
+    #[inline]
+    fn into_generated(self) -> Result<T::Generated, Box<dyn Error>> {
+        self.take().into_generated()
+    }
+}
+
+#[doc(hidden)]
+pub trait PlainGenerated {
+    type Generated: 'static;
+
+    fn into_generated(self) -> Result<Self::Generated, Box<dyn Error>>;
+}
+
+impl<T: 'static> PlainGenerated for &&GeneratorOutput<T> {
+    type Generated = T;
+
+    #[inline]
+    fn into_generated(self) -> Result<T, Box<dyn Error>> {
+        Ok(self.take())
+    }
+}
+
+/// Stands for the type returned by a generator, so that `(&GeneratedInput::<T>::new()).of(input)` gets back
+/// the input it gave to the solutions, picking `IntoGenerated` the same way as `GeneratorOutput`.
+#[doc(hidden)]
+pub struct GeneratedInput<T>(PhantomData<T>);
+
+impl<T> GeneratedInput<T> {
+    #[inline]
+    pub fn new() -> GeneratedInput<T> {
+        GeneratedInput(PhantomData)
+    }
+}
+
+impl<T> Default for GeneratedInput<T> {
+    fn default() -> GeneratedInput<T> {
+        GeneratedInput::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait FallibleInput {
+    type Input: 'static;
+
+    #[inline]
+    fn of(self, input: &dyn Any) -> Option<&Self::Input>
+    where
+        Self: Sized,
+    {
+        input.downcast_ref()
+    }
+
+    #[inline]
+    fn unprepare(self, prepared: Box<dyn Any>) -> Result<Box<Self::Input>, Box<dyn Any>>
+    where
+        Self: Sized,
+    {
+        prepared.downcast()
+    }
+}
+
+impl<T: IntoGenerated> FallibleInput for &GeneratedInput<T> {
+    type Input = T::Generated;
+}
+
+#[doc(hidden)]
+pub trait PlainInput {
+    type Input: 'static;
+
+    #[inline]
+    fn of(self, input: &dyn Any) -> Option<&Self::Input>
+    where
+        Self: Sized,
+    {
+        input.downcast_ref()
+    }
+
+    #[inline]
+    fn unprepare(self, prepared: Box<dyn Any>) -> Result<Box<Self::Input>, Box<dyn Any>>
+    where
+        Self: Sized,
+    {
+        prepared.downcast()
+    }
+}
+
+impl<T: 'static> PlainInput for &&GeneratedInput<T> {
+    type Input = T;
+}
+
+/// Lends the output of a generator to a solver taking `&T`, reporting a mismatch between them on the solver
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "the generator of day{DAY} part{PART} produces `{Self}`, which this solver cannot take as `&{T}`",
+    label = "cannot take the output of the generator",
+    note = "solvers taking their input by reference need the output of their generator to implement `Borrow<{T}>`",
+    note = "without a generator in their module, solvers are given the input as `ArcStr`: generators must be declared in the same module as the solutions using them"
+)]
+pub trait RefInput<T: ?Sized, const DAY: u8, const PART: u8> {
+    fn input_ref(&self) -> &T;
+}
+
+impl<I: Borrow<T>, T: ?Sized, const DAY: u8, const PART: u8> RefInput<T, DAY, PART> for I {
+    #[inline]
+    fn input_ref(&self) -> &T {
+        self.borrow()
+    }
+}
+
+/// Copies the output of a generator for a solver taking `&mut T`, reporting a mismatch between them on the solver
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "the generator of day{DAY} part{PART} produces `{Self}`, which this solver cannot take as `&mut {T}`",
+    label = "cannot take the output of the generator",
+    note = "solvers taking their input by `&mut` need the output of their generator to implement `Clone` & `BorrowMut<{T}>`",
+    note = "without a generator in their module, solvers are given the input as `ArcStr`: generators must be declared in the same module as the solutions using them"
+)]
+pub trait MutInput<T: ?Sized, const DAY: u8, const PART: u8>: Sized {
+    fn copy_input(&self) -> Self;
+
+    fn input_mut(&mut self) -> &mut T;
+}
+
+impl<I: Clone + BorrowMut<T>, T: ?Sized, const DAY: u8, const PART: u8> MutInput<T, DAY, PART> for I {
+    #[inline]
+    fn copy_input(&self) -> I {
+        self.clone()
+    }
+
+    #[inline]
+    fn input_mut(&mut self) -> &mut T {
+        self.borrow_mut()
+    }
+}
+
+/// Copies the output of a generator for a solver taking `T`, reporting a mismatch between them on the solver
+#[doc(hidden)]
+#[diagnostic::on_unimplemented(
+    message = "the generator of day{DAY} part{PART} produces `{Self}`, which this solver cannot take as `{T}`",
+    label = "cannot take the output of the generator",
+    note = "solvers taking their input by value need the output of their generator to be a `{T}`, implementing `Clone`",
+    note = "without a generator in their module, solvers are given the input as `ArcStr`: generators must be declared in the same module as the solutions using them"
+)]
+pub trait OwnedInput<T, const DAY: u8, const PART: u8> {
+    fn copy_input(&self) -> T;
+
+    fn into_input(self) -> T;
+}
+
+impl<I: Clone, const DAY: u8, const PART: u8> OwnedInput<I, DAY, PART> for I {
+    #[inline]
+    fn copy_input(&self) -> I {
+        self.clone()
+    }
+
+    #[inline]
+    fn into_input(self) -> I {
+        self
+    }
+}
+
 pub trait Runner {
     fn gen(input: ArcStr) -> Self
     where
@@ -55,4 +478,51 @@ pub trait Runner {
     fn try_run(&self) -> Result<Box<dyn Display>, Box<dyn Error>> {
         Ok(self.run())
     }
+
+    /// The output of the generator, to build the runners of other solutions using the same generator
+    /// without running it again
+    fn shared_input(&self) -> Option<Rc<dyn Any>> {
+        None
+    }
+
+    /// Copies the input of solvers taking it by value or by `&mut`, before benchmarking them with `bench_prepared`
+    fn prepare(&self) -> Box<dyn Any> {
+        Box::new(())
+    }
+
+    /// Same as `bench`, over an input copied beforehand by `prepare`.
+    ///
+    /// What is left of the input is given back, so that it gets dropped outside of the timings.
+    fn bench_prepared(&self, prepared: Box<dyn Any>, black_box: fn(&dyn Display)) -> Box<dyn Any> {
+        self.bench(black_box);
+        prepared
+    }
+
+    /// Same as `try_run`, over an input copied beforehand by `prepare`.
+    ///
+    /// What is left of the input is given back with the answer, so that it gets dropped outside of the timings.
+    fn try_run_prepared(&self, prepared: Box<dyn Any>) -> Result<(Answer, Box<dyn Any>), Box<dyn Error>> {
+        Ok((self.try_run()?, prepared))
+    }
+
+    /// Same as `try_run_both`, over an input copied beforehand by `prepare`
+    fn try_run_both_prepared(&self, prepared: Box<dyn Any>) -> Result<(BothAnswers, Box<dyn Any>), Box<dyn Error>> {
+        Ok((self.try_run_both()?, prepared))
+    }
+
+    /// Runs the solution again, for the state it gives along with its answer, to be used by part 2
+    fn try_state(&self) -> Result<Rc<dyn Any>, Box<dyn Error>> {
+        Err("this solution gives no state".into())
+    }
+
+    /// The state given by the default part 1 solution along with its answer, when it last ran with `try_run`
+    /// (or the state a part 2 runner was built with), to build the runners of part 2 without running part 1 again
+    fn shared_state(&self) -> Option<Rc<dyn Any>> {
+        None
+    }
+
+    /// Runs a solution of both parts (`#[aoc(dayN, both)]`) once, for the answers of part 1 and part 2
+    fn try_run_both(&self) -> Result<BothAnswers, Box<dyn Error>> {
+        Err("this solution only solves one part".into())
+    }
 }
@@ -0,0 +1,225 @@
+use crate::{known_answer, Answer, ArcStr, Runner};
+use std::any::Any;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::rc::Rc;
+
+/// The generator of a solution, looked up by `#[aoc]` in the module of the solution.
+///
+/// `#[aoc_generator]` implements it for a hidden type next to the generator function,
+/// shadowing the default of `no_generator`.
+#[doc(hidden)]
+pub trait Generator {
+    /// Type returned by the generator function, given to the solutions as is or through `IntoGenerated`
+    type Output: 'static;
+
+    /// Name of the generator function, `None` for solutions taking the input as is
+    const NAME: Option<&'static str>;
+
+    /// Full path of the generator function, identifying its output among those of the other generators
+    const PATH: Option<&'static str>;
+
+    /// Runs the generator, giving the input of the solutions
+    fn generate(input: &ArcStr) -> Result<Rc<dyn Any>, Box<dyn Error>>;
+}
+
+/// The defaults of the generators looked up by `#[aoc]`, giving the input as is
+#[doc(hidden)]
+pub mod no_generator {
+    use super::Generator;
+    use crate::ArcStr;
+    use std::any::Any;
+    use std::error::Error;
+    use std::rc::Rc;
+
+    macro_rules! no_generators {
+        ($($name: ident)*) => {
+            $(
+                pub struct $name;
+
+                impl Generator for $name {
+                    type Output = ArcStr;
+
+                    const NAME: Option<&'static str> = None;
+
+                    const PATH: Option<&'static str> = None;
+
+                    #[inline]
+                    fn generate(input: &ArcStr) -> Result<Rc<dyn Any>, Box<dyn Error>> {
+                        Ok(Rc::new(input.clone()))
+                    }
+                }
+            )*
+        };
+    }
+
+    no_generators! {
+        __AocGeneratorDay1Part1 __AocGeneratorDay1Part2
+        __AocGeneratorDay2Part1 __AocGeneratorDay2Part2
+        __AocGeneratorDay3Part1 __AocGeneratorDay3Part2
+        __AocGeneratorDay4Part1 __AocGeneratorDay4Part2
+        __AocGeneratorDay5Part1 __AocGeneratorDay5Part2
+        __AocGeneratorDay6Part1 __AocGeneratorDay6Part2
+        __AocGeneratorDay7Part1 __AocGeneratorDay7Part2
+        __AocGeneratorDay8Part1 __AocGeneratorDay8Part2
+        __AocGeneratorDay9Part1 __AocGeneratorDay9Part2
+        __AocGeneratorDay10Part1 __AocGeneratorDay10Part2
+        __AocGeneratorDay11Part1 __AocGeneratorDay11Part2
+        __AocGeneratorDay12Part1 __AocGeneratorDay12Part2
+        __AocGeneratorDay13Part1 __AocGeneratorDay13Part2
+        __AocGeneratorDay14Part1 __AocGeneratorDay14Part2
+        __AocGeneratorDay15Part1 __AocGeneratorDay15Part2
+        __AocGeneratorDay16Part1 __AocGeneratorDay16Part2
+        __AocGeneratorDay17Part1 __AocGeneratorDay17Part2
+        __AocGeneratorDay18Part1 __AocGeneratorDay18Part2
+        __AocGeneratorDay19Part1 __AocGeneratorDay19Part2
+        __AocGeneratorDay20Part1 __AocGeneratorDay20Part2
+        __AocGeneratorDay21Part1 __AocGeneratorDay21Part2
+        __AocGeneratorDay22Part1 __AocGeneratorDay22Part2
+        __AocGeneratorDay23Part1 __AocGeneratorDay23Part2
+        __AocGeneratorDay24Part1 __AocGeneratorDay24Part2
+        __AocGeneratorDay25Part1 __AocGeneratorDay25Part2
+    }
+}
+
+/// Builds the runner of a solution, from the input or from the output of its generator
+type BuildRunner<I> = fn(I) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// Builds the runner of a part 2 solution from the input (or the output of its generator, if already run),
+/// and from the state given by part 1
+type BuildStatedRunner = fn(ArcStr, Option<Rc<dyn Any>>, Rc<dyn Any>) -> Result<Box<dyn Runner>, Box<dyn Error>>;
+
+/// A solution registered by `#[aoc]`, collected when linking
+#[doc(hidden)]
+pub struct Solution {
+    pub year: u32,
+    pub day: u8,
+    pub part: u8,
+    pub name: Option<&'static str>,
+    /// Name of the generator function, if any
+    pub generator: Option<&'static str>,
+    /// Full path of the generator function, shared by the solutions using the same generator
+    pub generator_path: Option<&'static str>,
+    /// Solves both parts in a single call
+    pub both: bool,
+    pub runner: BuildRunner<ArcStr>,
+    /// Builds the runner from the output of the generator, already run for another solution
+    pub shared_runner: BuildRunner<Rc<dyn Any>>,
+    /// For part 2 solutions, builds the runner from the state part 1 gave when it ran, instead of running it again
+    pub state_runner: Option<BuildStatedRunner>,
+}
+
+inventory::collect!(Solution);
+
+impl Solution {
+    pub(crate) fn title(&self, part: u8) -> String {
+        SolutionInfo { part, ..self.info() }.to_string()
+    }
+
+    fn info(&self) -> SolutionInfo {
+        SolutionInfo {
+            year: self.year,
+            day: self.day,
+            part: self.part,
+            name: self.name,
+            has_generator: self.generator.is_some(),
+        }
+    }
+}
+
+/// All the solutions linked in the binary, by year, day, part & name
+#[doc(hidden)]
+pub fn registered() -> Vec<&'static Solution> {
+    let mut solutions: Vec<_> = inventory::iter::<Solution>.into_iter().collect();
+    solutions.sort_by_key(|s| (s.year, s.day, s.part, s.name));
+    solutions
+}
+
+/// A solution flagged with `#[aoc]`, in a crate linked in the binary
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SolutionInfo {
+    pub year: u32,
+    pub day: u8,
+    pub part: u8,
+    /// The name given in `#[aoc(dayN, partM, Name)]`, `None` for the default solution
+    pub name: Option<&'static str>,
+    pub has_generator: bool,
+}
+
+impl fmt::Display for SolutionInfo {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "Day {} - Part {} - {}", self.day, self.part, name),
+            None => write!(f, "Day {} - Part {}", self.day, self.part),
+        }
+    }
+}
+
+/// Lists the solutions of every crate linked in the binary, sorted by year, day, part & name.
+///
+/// A solution of both parts (`#[aoc(dayN, both)]`) is listed for each of them.
+pub fn solutions() -> Vec<SolutionInfo> {
+    registered().into_iter().map(Solution::info).collect()
+}
+
+/// Runs a solution over an input, generator included, as `cargo aoc` does
+///
+/// ```ignore
+/// for solution in aoc_runner::solutions() {
+///     println!("{}: {}", solution, aoc_runner::run(&solution, &input)?);
+/// }
+/// ```
+pub fn run(solution: &SolutionInfo, input: &str) -> Result<Answer, Box<dyn Error>> {
+    let registered = registered()
+        .into_iter()
+        .find(|s| s.info() == *solution)
+        .ok_or_else(|| format!("{} of {} is not linked in this binary", solution, solution.year))?;
+
+    (registered.runner)(ArcStr::from(input))?.try_run()
+}
+
+pub(crate) fn input_path(crate_dir: &str, year: u32, day: u8) -> String {
+    format!("{}/input/{}/day{}.txt", crate_dir, year, day)
+}
+
+/// Checks a solution against its known answer in `answers/{year}.toml`, for the tests generated by
+/// `aoc_lib! { tests = true }`.
+///
+/// Skipped without an input or a known answer.
+#[doc(hidden)]
+pub fn test_answer(year: u32, day: u8, part: u8, name: Option<&str>, crate_dir: &str) {
+    let solution = registered()
+        .into_iter()
+        .find(|s| (s.year, s.day, s.part, s.name) == (year, day, part, name))
+        .expect("solution is not registered");
+    let title = solution.title(part);
+
+    let input_path = input_path(crate_dir, year, day);
+    let input = match fs::read_to_string(&input_path) {
+        Ok(input) => input,
+        Err(_) => {
+            eprintln!("{}: skipped, no input found at {}", title, input_path);
+            return;
+        }
+    };
+
+    let answers_path = format!("{}/answers/{}.toml", crate_dir, year);
+    let answers: toml::Value = fs::read_to_string(&answers_path)
+        .unwrap_or_default()
+        .parse()
+        .unwrap_or_else(|e| panic!("{} is not valid TOML : {}", answers_path, e));
+    let expected = match known_answer(&answers, day, part) {
+        Some(expected) => expected,
+        None => {
+            eprintln!("{}: skipped, no known answer in {}", title, answers_path);
+            return;
+        }
+    };
+
+    let answer = (solution.runner)(ArcStr::from(&input))
+        .and_then(|runner| runner.try_run())
+        .unwrap_or_else(|e| panic!("{}: FAILED: {}", title, e));
+
+    assert_eq!(answer.to_string(), expected, "wrong answer for {}", title);
+}
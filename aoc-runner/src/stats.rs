@@ -0,0 +1,39 @@
+use std::fmt;
+use std::time::Duration;
+
+/// Timings of a step over many runs, as printed by `cargo aoc --repeat` and the `aoc_main!` binaries
+#[doc(hidden)]
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub stddev: Duration,
+}
+
+impl Stats {
+    pub fn of(times: &[Duration]) -> Stats {
+        let mut sorted = times.to_vec();
+        sorted.sort();
+
+        let secs: Vec<f64> = sorted.iter().map(|t| t.as_secs_f64()).collect();
+        let mean = secs.iter().sum::<f64>() / secs.len() as f64;
+        let variance = secs.iter().map(|s| (s - mean) * (s - mean)).sum::<f64>() / secs.len() as f64;
+
+        Stats {
+            min: sorted[0],
+            median: sorted[sorted.len() / 2],
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+impl fmt::Display for Stats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "min {:?}, median {:?}, mean {:?}, stddev {:?}",
+            self.min, self.median, self.mean, self.stddev
+        )
+    }
+}
@@ -0,0 +1,158 @@
+use aoc_runner_internal::{Day, DayPart, Part};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::BTreeMap;
+use std::error;
+use std::fs;
+
+/// Known correct answers of a year, stored in `answers/{year}.toml` :
+///
+/// ```toml
+/// [day1]
+/// part1 = "516"
+/// part2 = "71892"
+/// ```
+pub struct AnswerStore {
+    path: Utf8PathBuf,
+    answers: BTreeMap<Day, BTreeMap<Part, String>>,
+}
+
+impl AnswerStore {
+    /// Loads the answers of the given year, or an empty store if there is none yet
+    pub fn load(crate_dir: &Utf8Path, year: u32) -> Result<Self, Box<dyn error::Error>> {
+        let path = crate_dir.join("answers").join(format!("{}.toml", year));
+
+        let mut answers = BTreeMap::new();
+        if path.exists() {
+            let content: toml::Value = fs::read_to_string(&path)?.parse()?;
+            let days = content
+                .as_table()
+                .ok_or_else(|| format!("{} is not a table", path))?;
+
+            for (day, parts) in days {
+                let day: Day = day.parse()?;
+                let parts = parts
+                    .as_table()
+                    .ok_or_else(|| format!("{}: day{} is not a table", path, day.0))?;
+
+                let entry: &mut BTreeMap<Part, String> = answers.entry(day).or_default();
+                for (part, answer) in parts {
+                    let answer = match answer {
+                        toml::Value::String(s) => s.clone(),
+                        toml::Value::Integer(i) => i.to_string(),
+                        _ => return Err(format!("{}: invalid answer for {}", path, part).into()),
+                    };
+                    entry.insert(part.parse()?, answer);
+                }
+            }
+        }
+
+        Ok(AnswerStore { path, answers })
+    }
+
+    pub fn get(&self, day: Day, part: Part) -> Option<&str> {
+        self.answers
+            .get(&day)
+            .and_then(|parts| parts.get(&part))
+            .map(|a| a.as_str())
+    }
+
+    pub fn set(&mut self, day: Day, part: Part, answer: String) {
+        self.answers.entry(day).or_default().insert(part, answer);
+    }
+
+    /// Writes the store back to disk, days in order
+    pub fn save(&self) -> Result<(), Box<dyn error::Error>> {
+        let mut content = String::new();
+        for (day, parts) in &self.answers {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content += &format!("[day{}]\n", day.0);
+            for (part, answer) in parts {
+                content += &format!(
+                    "part{} = {}\n",
+                    part.0,
+                    toml::Value::String(answer.clone())
+                );
+            }
+        }
+
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, content)?;
+
+        Ok(())
+    }
+}
+
+/// Extracts the answers already accepted from a puzzle page, in part order
+pub fn scrape_answers(page: &str) -> Vec<String> {
+    const PREFIX: &str = "Your puzzle answer was <code>";
+
+    page.match_indices(PREFIX)
+        .filter_map(|(i, _)| {
+            let answer = &page[i + PREFIX.len()..];
+            answer.find("</code>").map(|end| answer[..end].to_string())
+        })
+        .collect()
+}
+
+/// An answer given by a solution over one of the inputs
+pub struct Answer {
+    pub input: String,
+    pub day_part: DayPart,
+    pub answer: String,
+}
+
+/// Parses an answer printed by the generated `answer.rs` template :
+/// `ANSWER\t{input}\t{day}\t{part}\t{name}\t{answer:?}`
+pub fn parse_answer_line(line: &str) -> Option<Answer> {
+    let mut fields = line.splitn(6, '\t');
+
+    if fields.next()? != "ANSWER" {
+        return None;
+    }
+
+    let input = fields.next()?.to_string();
+    let day = fields.next()?.parse().ok()?;
+    let part = fields.next()?.parse().ok()?;
+    let name = match fields.next()? {
+        "" => None,
+        name => Some(name.to_string()),
+    };
+    let answer = unescape_debug(fields.next()?)?;
+
+    Some(Answer {
+        input,
+        day_part: DayPart { day, part, name },
+        answer,
+    })
+}
+
+/// Reverts the escaping done by `Debug` on a `String`
+fn unescape_debug(s: &str) -> Option<String> {
+    let mut chars = s.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut out = String::new();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+
+        out.push(match chars.next()? {
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            '0' => '\0',
+            'u' => {
+                let code: String = chars.by_ref().skip(1).take_while(|&c| c != '}').collect();
+                std::char::from_u32(u32::from_str_radix(&code, 16).ok()?)?
+            }
+            c => c,
+        });
+    }
+
+    Some(out)
+}
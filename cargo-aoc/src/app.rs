@@ -1,22 +1,37 @@
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use clap::ArgMatches;
 use reqwest::header::COOKIE;
 use reqwest::Client;
 use reqwest::StatusCode;
+use std::collections::HashMap;
 use std::error;
 use std::fs;
 use std::fs::File;
-use std::io::Write;
+use std::io::{self, IsTerminal, Read, Write};
 use std::path::Path;
 use std::process;
+use std::time::SystemTime;
 
+use crate::answers::{parse_answer_line, scrape_answers, Answer, AnswerStore};
+use crate::cachegrind::{self, CachegrindResult, Counts};
 use crate::credentials::CredentialsManager;
+use crate::criterion;
 use crate::date::AOCDate;
-use crate::project::ProjectManager;
-use crate::template::get_tera;
+use crate::environment::Environment;
+use crate::history::{self, History};
+use crate::project::{ProjectManager, Worktree};
+use crate::template::{
+    BenchGen, BenchGenImpl, BenchImpl, BenchMain, BenchPart, Input, Inputs, Manifest, ProfileMain,
+    QuickBench, RunMain, Solution, SolutionsMain, Templates,
+};
 use aoc_runner_internal::Day;
+use aoc_runner_internal::DayPart;
+use aoc_runner_internal::DayParts;
 use aoc_runner_internal::Part;
 
+/// Untimed runs before timing a solution many times, unless `--warm-up` says otherwise
+pub const DEFAULT_WARM_UP: &str = "10";
+
 pub struct AOCApp {}
 
 impl AOCApp {
@@ -130,7 +145,7 @@ impl AOCApp {
 
                 // Gets the body from the response and outputs everything to a file
                 let body = response.text()?;
-                let mut file = File::create(&filename)?;
+                let mut file = File::create(filename)?;
                 file.write_all(body.as_bytes())?;
             }
             sc => return Err(format!(
@@ -142,7 +157,51 @@ impl AOCApp {
         Ok(())
     }
 
+    /// Resolves the input files given with `-i`, defaulting to the downloaded input of the day.
+    ///
+    /// `-` reads the input from stdin, and stores it in `stdin_dir` so it can be included.
+    fn input_files(
+        &self,
+        args: &ArgMatches,
+        day: Day,
+        year: u32,
+        default: Utf8PathBuf,
+        stdin_dir: &Utf8Path,
+    ) -> Result<Vec<(String, Utf8PathBuf)>, Box<dyn error::Error>> {
+        let values = match args.values_of("input") {
+            Some(values) => values,
+            None => {
+                self.download_input(day, year)?;
+                return Ok(vec![(default.to_string(), default)]);
+            }
+        };
+
+        let mut inputs = Vec::new();
+        for value in values {
+            if value == "-" {
+                if inputs.iter().any(|(name, _)| name == "stdin") {
+                    return Err("stdin can only be used once as an input".into());
+                }
+
+                let mut content = String::new();
+                io::stdin().read_to_string(&mut content)?;
+
+                fs::create_dir_all(stdin_dir)?;
+                let path = stdin_dir.join(format!("day{}.txt", day.0));
+                fs::write(&path, content)?;
+
+                inputs.push(("stdin".to_string(), path));
+            } else {
+                inputs.push((value.to_string(), value.into()));
+            }
+        }
+
+        Ok(inputs)
+    }
+
     pub fn execute_default(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        export_params(args)?;
+
         let day: Option<Day> = args
             .value_of("day")
             .map(|d| d.parse().expect("Failed to parse day"));
@@ -158,99 +217,173 @@ impl AOCApp {
         let day = day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day);
         let year = day_parts.year;
 
-        let cargo_content = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/Cargo-run.toml.tpl"
-        ))
-        .replace("{CRATE_NAME}", &pm.name)
-        .replace(
-            "{CRATE_PATH}",
-            &pm.crate_dir
-                .clone()
-                .into_string()
-                .escape_default()
-                .to_string(),
-        )
-        .replace(
-            "{PROFILE}",
-            if args.is_present("profile") {
-                "[profile.release]\ndebug = true"
-            } else {
-                ""
-            },
-        );
+        // With a repeat count, each solution is timed many times instead of once
+        let repeat: Option<u32> = args
+            .value_of("repeat")
+            .map(|r| r.parse().expect("Failed to parse repeat count"));
+        let warm_up: u32 = args
+            .value_of("warm-up")
+            .unwrap_or(DEFAULT_WARM_UP)
+            .parse()
+            .expect("Failed to parse warm-up count");
+
+        if repeat == Some(0) {
+            return Err("Repeat count must be at least 1".into());
+        }
+
+        let templates = Templates::load(&pm.crate_dir)?;
+        let autobuild_dir = autobuild_dir(&pm);
 
-        let template = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/src/runner.rs.tpl"
-        ));
+        let selected: Vec<&DayPart> = day_parts
+            .iter()
+            .filter(|dp| dp.day == day)
+            .filter(|dp| {
+                if let Some(p) = part {
+                    dp.part == p
+                } else {
+                    true
+                }
+            })
+            .collect();
 
         let mut body = String::new();
-        for dp in day_parts.iter().filter(|dp| dp.day == day).filter(|dp| {
-            if let Some(p) = part {
-                dp.part == p
-            } else {
-                true
+        for &dp in &selected {
+            // Each timed run gets its own generator output, and its own call to a solution of both parts
+            let mut solution = solution(dp);
+            if repeat.is_none() {
+                solution.generator = day_parts.generator(dp).map(|g| g.path.as_str());
+
+                if day_parts.solves_both(dp) {
+                    let mut second = dp.clone();
+                    second.part = Part(2);
+
+                    if dp.part == Part(1) && selected.contains(&&second) {
+                        solution.both = Some(runner_names(&second).1);
+                    } else if dp.part == Part(2) && selected.iter().any(|d| d.part == Part(1) && d.name == dp.name) {
+                        // Already reported along with part 1
+                        continue;
+                    }
+                }
             }
-        }) {
-            let (name, display) = if let Some(n) = &dp.name {
-                (
-                    format!("day{}_part{}_{}", dp.day.0, dp.part.0, n.to_lowercase()),
-                    format!("Day {} - Part {} - {}", dp.day.0, dp.part.0, n),
-                )
-            } else {
-                (
-                    format!("day{}_part{}", dp.day.0, dp.part.0),
-                    format!("Day {} - Part {}", dp.day.0, dp.part.0),
-                )
-            };
 
-            body += &template
-                .replace("{DAY}", &day.0.to_string())
-                .replace("{RUNNER_NAME}", &name)
-                .replace("{RUNNER_DISPLAY}", &display);
+            body += &match repeat {
+                Some(repeat) => templates.render(
+                    "src/quick_bench.rs.tpl",
+                    &QuickBench {
+                        solution: &solution,
+                        repeat,
+                        warm_up,
+                    },
+                )?,
+                None => templates.render("src/runner.rs.tpl", &solution)?,
+            };
         }
 
         if body.is_empty() {
             return Err("No matching day & part found".into());
         }
 
-        self.download_input(day, year)?;
-        let def_input_file = pm.input_file_for(year, day);
-
-        let main_content = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/src/main.rs.tpl"
-        ))
-        .replace("{CRATE_SLUG}", &pm.slug)
-        .replace("{YEAR}", &day_parts.year.to_string())
-        .replace(
-            "{INPUT}",
-            &template_input(
-                day,
+        let inputs = self.input_files(
+            args,
+            day,
+            year,
+            pm.input_file_for(year, day),
+            &autobuild_dir.join("input"),
+        )?;
+
+        let main_content = templates.render(
+            "src/main.rs.tpl",
+            &RunMain {
+                crate_slug: &pm.slug,
                 year,
-                args.value_of("input")
-                    .unwrap_or_else(|| def_input_file.as_str()),
-            )?,
-        )
-        .replace("{BODY}", &body);
+                day: day.0,
+                input: template_input(&templates, day, &inputs)?,
+                body,
+                stats: repeat.is_some(),
+            },
+        )?;
+
+        if let Some(repeat) = repeat {
+            println!("Timing {} runs, after {} warm-up runs", repeat, warm_up);
+        }
+
+        write_autobuild(
+            &templates,
+            &pm,
+            &autobuild_dir,
+            args.is_present("profile"),
+            &main_content,
+        )?;
+
+        let status = process::Command::new("cargo")
+            .args(["run", "--release"])
+            .current_dir(autobuild_dir)
+            .spawn()
+            .expect("Failed to run cargo")
+            .wait()
+            .expect("Failed to wait for cargo");
+
+        if !status.success() {
+            process::exit(status.code().unwrap_or(-1));
+        }
 
-        let autobuild_dir = pm
+        Ok(())
+    }
+
+    /// Counts the instructions executed by each solution under Cachegrind, which unlike
+    /// wall-clock time does not depend on the load of the machine.
+    fn bench_cachegrind(
+        &self,
+        args: &ArgMatches,
+        pm: &ProjectManager,
+        day_parts: &DayParts,
+        part: Option<Part>,
+        inputs: &[(Day, Vec<(String, Utf8PathBuf)>)],
+    ) -> Result<(), Box<dyn error::Error>> {
+        let templates = Templates::load(&pm.crate_dir)?;
+
+        // `Option::is_none_or` needs Rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let selected: Vec<&DayPart> = day_parts
+            .iter()
+            .filter(|dp| inputs.iter().any(|(day, _)| *day == dp.day))
+            .filter(|dp| part.map_or(true, |p| dp.part == p))
+            .collect();
+
+        if selected.is_empty() {
+            return Err("No matching day & part found".into());
+        }
+
+        let cases = selected
+            .iter()
+            .map(|dp| templates.render("src/cachegrind_case.rs.tpl", &solution(dp)))
+            .collect::<Result<String, _>>()?;
+
+        let templated_inputs = inputs
+            .iter()
+            .map(|(day, files)| template_input(&templates, *day, files))
+            .collect::<Result<String, _>>()?;
+
+        let main_content = templates.render(
+            "src/cachegrind.rs.tpl",
+            &SolutionsMain {
+                crate_slug: &pm.slug,
+                inputs: templated_inputs,
+                body: cases,
+            },
+        )?;
+
+        let autocachegrind_dir = pm
             .root_target_dir
             .join("aoc")
-            .join(pm.slug)
-            .join("aoc-autobuild");
+            .join(&pm.slug)
+            .join("aoc-autocachegrind");
 
-        fs::create_dir_all(autobuild_dir.join("src"))
-            .expect("failed to create autobuild directory");
-        fs::write(autobuild_dir.join("Cargo.toml"), &cargo_content)
-            .expect("failed to write Cargo.toml");
-        fs::write(autobuild_dir.join("src/main.rs"), &main_content)
-            .expect("failed to write src/main.rs");
+        write_autobuild(&templates, pm, &autocachegrind_dir, true, &main_content)?;
 
         let status = process::Command::new("cargo")
-            .args(&["run", "--release"])
-            .current_dir(autobuild_dir)
+            .args(["build", "--release"])
+            .current_dir(&autocachegrind_dir)
             .spawn()
             .expect("Failed to run cargo")
             .wait()
@@ -260,10 +393,76 @@ impl AOCApp {
             process::exit(status.code().unwrap_or(-1));
         }
 
+        let binary = autocachegrind_dir
+            .join("target/release")
+            .join(format!("aoc-autobuild{}", std::env::consts::EXE_SUFFIX));
+
+        let cachegrind = |runner_name: &str, mode: &str| -> Result<Counts, Box<dyn error::Error>> {
+            let out_file = autocachegrind_dir.join(format!("cachegrind.out.{}", mode));
+            let output = process::Command::new("valgrind")
+                .arg("--tool=cachegrind")
+                .arg("--cache-sim=yes")
+                .arg(format!("--cachegrind-out-file={}", out_file))
+                .arg(&binary)
+                .args([runner_name, mode])
+                .output()
+                .map_err(|_| "valgrind is not installed, it is needed to run Cachegrind")?;
+
+            if !output.status.success() {
+                return Err(format!(
+                    "{} failed under Cachegrind:\n{}",
+                    runner_name,
+                    String::from_utf8_lossy(&output.stderr)
+                )
+                .into());
+            }
+
+            Counts::read(&out_file)
+        };
+
+        let mut results = Vec::new();
+        for dp in selected {
+            let (runner_name, _) = runner_names(dp);
+            let calibration = cachegrind(&runner_name, "calibrate")?;
+            let counts = cachegrind(&runner_name, "run")?.without(calibration);
+
+            results.push(CachegrindResult {
+                day: dp.day.0,
+                part: dp.part.0,
+                name: dp.name.clone().unwrap_or_else(|| "(default)".to_string()),
+                counts,
+            });
+        }
+
+        let results_file = autocachegrind_dir.join("results.json");
+        let previous = cachegrind::load(&results_file)?;
+
+        println!();
+        let threshold: f64 = args
+            .value_of("threshold")
+            .unwrap_or("5")
+            .parse()
+            .expect("Failed to parse threshold");
+        let regressions = cachegrind::report(&results, &previous, threshold);
+
+        cachegrind::save(&results_file, &results, previous)?;
+
+        if args.is_present("compare") && regressions > 0 {
+            return Err(format!(
+                "{} solution(s) executed more than {}% more instructions than on the previous run",
+                regressions, threshold
+            )
+            .into());
+        }
+
         Ok(())
     }
 
-    pub fn execute_bench(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+    /// Builds a release binary with debug info that runs a single solution over and over,
+    /// and launches it under a profiler.
+    pub fn execute_profile(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        let params = export_params(args)?;
+
         let day: Option<Day> = args
             .value_of("day")
             .map(|d| d.parse().expect("Failed to parse day"));
@@ -279,217 +478,738 @@ impl AOCApp {
         let day = day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day);
         let year = day_parts.year;
 
-        let cargo_content = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/Cargo-bench.toml.tpl"
-        ))
-        .replace("{CRATE_NAME}", &pm.name)
-        .replace(
-            "{CRATE_PATH}",
-            &pm.crate_dir
-                .clone()
-                .into_string()
-                .escape_default()
-                .to_string(),
-        )
-        .replace(
-            "{PROFILE}",
-            if args.is_present("profile") {
-                "[profile.release]\ndebug = true"
-            } else {
-                ""
+        // `Option::is_none_or` needs Rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let dp = day_parts
+            .iter()
+            .filter(|dp| dp.day == day)
+            .rfind(|dp| {
+                part.map_or(true, |p| dp.part == p) && dp.name.as_deref() == args.value_of("name")
+            })
+            .ok_or("No matching day & part found")?;
+        let solution = solution(dp);
+        let display = solution.runner_display.clone();
+
+        let iterations: Option<u64> = args
+            .value_of("iterations")
+            .map(|i| i.parse().expect("Failed to parse iterations"));
+        let seconds: f64 = args
+            .value_of("seconds")
+            .unwrap_or("10")
+            .parse()
+            .expect("Failed to parse seconds");
+
+        let autoprofile_dir = pm
+            .root_target_dir
+            .join("aoc")
+            .join(&pm.slug)
+            .join("aoc-autoprofile");
+
+        let inputs = self.input_files(
+            args,
+            day,
+            year,
+            pm.input_file_for(year, day),
+            &autoprofile_dir.join("input"),
+        )?;
+
+        let templates = Templates::load(&pm.crate_dir)?;
+        let main_content = templates.render(
+            "src/profile.rs.tpl",
+            &ProfileMain {
+                solution: &solution,
+                crate_slug: &pm.slug,
+                input: template_input(&templates, day, &inputs)?,
+                only: args.value_of("only"),
+                iterations,
+                seconds,
             },
-        );
+        )?;
 
-        let bench_tpl = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/benches/aoc_benchmark.rs.tpl"
-        ));
-
-        let part_tpl = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/benches/part.rs.tpl"
-        ));
-
-        let gen_tpl = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/benches/gen.rs.tpl"
-        ));
-
-        let impl_tpl = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/benches/impl.rs.tpl"
-        ));
-
-        let gen_impl_tpl = include_str!(concat!(
-            env!("CARGO_MANIFEST_DIR"),
-            "/template/benches/gen_impl.rs.tpl"
-        ));
-
-        let matching_parts = day_parts.iter().filter(|dp| dp.day == day).filter(|dp| {
-            if let Some(p) = part {
-                dp.part == p
-            } else {
-                true
+        write_autobuild(&templates, &pm, &autoprofile_dir, true, &main_content)?;
+
+        let status = process::Command::new("cargo")
+            .args(["build", "--release"])
+            .current_dir(&autoprofile_dir)
+            .spawn()
+            .expect("Failed to run cargo")
+            .wait()
+            .expect("Failed to wait for cargo");
+
+        if !status.success() {
+            process::exit(status.code().unwrap_or(-1));
+        }
+
+        let binary = autoprofile_dir
+            .join("target/release")
+            .join(format!("aoc-autobuild{}", std::env::consts::EXE_SUFFIX));
+
+        let (program, tool_args, output) = match args.value_of("tool") {
+            Some("perf") => {
+                let output = autoprofile_dir.join("perf.data");
+                let tool_args = vec![
+                    "record".to_string(),
+                    "-g".to_string(),
+                    "-o".to_string(),
+                    output.to_string(),
+                ];
+                ("perf", tool_args, output)
             }
-        });
+            Some("callgrind") => {
+                let output = autoprofile_dir.join("callgrind.out");
+                let tool_args = vec![
+                    "--tool=callgrind".to_string(),
+                    format!("--callgrind-out-file={}", output),
+                ];
+                ("valgrind", tool_args, output)
+            }
+            _ => {
+                match params {
+                    Some(params) => println!(
+                        "\nProfiling binary for {} : {}={} {}",
+                        display, PARAMS_ENV, params, binary
+                    ),
+                    None => println!("\nProfiling binary for {} : {}", display, binary),
+                }
+                return Ok(());
+            }
+        };
 
-        let mut parts: Vec<_> = matching_parts.clone().map(|dp| dp.part).collect();
-        parts.sort();
-        parts.dedup();
+        if process::Command::new(program)
+            .arg("--version")
+            .stdout(process::Stdio::null())
+            .stderr(process::Stdio::null())
+            .status()
+            .is_err()
+        {
+            return Err(format!(
+                "{} is not installed, run {} under your profiler of choice",
+                program, binary
+            )
+            .into());
+        }
 
-        let body: String = parts
-            .into_iter()
-            .map(|p| {
-                let part_name = format!("day{}_part{}", day.0, p.0);
-                part_tpl
-                    .replace("{PART_NAME}", &part_name)
-                    .replace("{DAY}", &day.0.to_string())
-                    .replace("{PART}", &p.0.to_string())
-                    .replace(
-                        "{IMPLS}",
-                        &matching_parts
-                            .clone()
-                            .filter(|dp| dp.part == p)
-                            .map(|dp| {
-                                impl_tpl
-                                    .replace(
-                                        "{RUNNER_NAME}",
-                                        &if let Some(n) = &dp.name {
-                                            format!(
-                                                "day{}_part{}_{}",
-                                                dp.day.0,
-                                                dp.part.0,
-                                                n.to_lowercase()
-                                            )
-                                        } else {
-                                            format!("day{}_part{}", dp.day.0, dp.part.0)
-                                        },
-                                    )
-                                    .replace("{DAY}", &dp.day.0.to_string())
-                                    .replace(
-                                        "{NAME}",
-                                        if let Some(n) = &dp.name {
-                                            n
-                                        } else {
-                                            "(default)"
-                                        },
-                                    )
-                                    .replace("{PART_NAME}", &part_name)
-                            })
-                            .collect::<String>(),
-                    )
+        let status = process::Command::new(program)
+            .args(&tool_args)
+            .arg(&binary)
+            .spawn()?
+            .wait()?;
+
+        if !status.success() {
+            return Err(format!(
+                "{} failed with code {}",
+                program,
+                status.code().unwrap_or(-1)
+            )
+            .into());
+        }
+
+        println!("\nProfile written to {}", output);
+
+        Ok(())
+    }
+
+    /// Builds & runs the given solutions over the inputs of their day,
+    /// and returns the answers they produced for each input.
+    fn collect_answers(
+        &self,
+        templates: &Templates,
+        pm: &ProjectManager,
+        day_parts: &[&DayPart],
+        inputs: &[(Day, Vec<(String, Utf8PathBuf)>)],
+    ) -> Result<Vec<Answer>, Box<dyn error::Error>> {
+        let inputs = inputs
+            .iter()
+            .map(|(day, files)| template_input(templates, *day, files))
+            .collect::<Result<String, _>>()?;
+
+        let body = day_parts
+            .iter()
+            .map(|dp| templates.render("src/answer.rs.tpl", &solution(dp)))
+            .collect::<Result<String, _>>()?;
+
+        let main_content = templates.render(
+            "src/answers.rs.tpl",
+            &SolutionsMain {
+                crate_slug: &pm.slug,
+                inputs,
+                body,
+            },
+        )?;
+
+        let autobuild_dir = autobuild_dir(pm);
+        write_autobuild(templates, pm, &autobuild_dir, false, &main_content)?;
+
+        let output = process::Command::new("cargo")
+            .args(["run", "--release"])
+            .current_dir(autobuild_dir)
+            .stdout(process::Stdio::piped())
+            .spawn()
+            .expect("Failed to run cargo")
+            .wait_with_output()
+            .expect("Failed to wait for cargo");
+
+        if !output.status.success() {
+            return Err(format!(
+                "cargo run failed with code {}",
+                output.status.code().unwrap_or(-1)
+            )
+            .into());
+        }
+
+        Ok(String::from_utf8(output.stdout)?
+            .lines()
+            .filter_map(parse_answer_line)
+            .collect())
+    }
+
+    /// Fills the known answers of the given days, by scraping the pages of already solved puzzles
+    fn fetch_answers(
+        &self,
+        store: &mut AnswerStore,
+        year: u32,
+        days: &[Day],
+    ) -> Result<(), Box<dyn error::Error>> {
+        let token = CredentialsManager::new().get_session_token()?;
+        // Creates an HTTP Client
+        let client = Client::new();
+        // Cookie formatting ...
+        let formated_token = format!("session={}", token);
+
+        for &day in days {
+            if store.get(day, Part(1)).is_some() && store.get(day, Part(2)).is_some() {
+                continue;
+            }
+
+            let date = AOCDate {
+                day: u32::from(day.0),
+                year: year as i32,
+            };
+
+            let mut response = client
+                .get(&date.puzzle_url())
+                .header(COOKIE, formated_token.as_str())
+                .send()?;
+
+            if response.status() != StatusCode::OK {
+                return Err(format!(
+                    "Could not fetch the puzzle of day {}. Status: {}",
+                    day.0,
+                    response.status()
+                )
+                .into());
+            }
+
+            for (i, answer) in scrape_answers(&response.text()?).into_iter().enumerate() {
+                store.set(day, Part(i as u8 + 1), answer);
+            }
+        }
+
+        store.save()
+    }
+
+    pub fn execute_verify(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        let day: Option<Day> = args
+            .value_of("day")
+            .map(|d| d.parse().expect("Failed to parse day"));
+
+        let part: Option<Part> = args
+            .value_of("part")
+            .map(|p| p.parse().expect("Failed to parse part"));
+
+        let pm = ProjectManager::new()?;
+
+        let day_parts = pm.build_project()?;
+        let year = day_parts.year;
+
+        let mut days: Vec<Day> = if args.is_present("all") {
+            day_parts.iter().map(|dp| dp.day).collect()
+        } else {
+            vec![day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day)]
+        };
+        days.dedup();
+
+        let mut store = AnswerStore::load(&pm.crate_dir, year)?;
+        if args.is_present("fetch") {
+            self.fetch_answers(&mut store, year, &days)?;
+        }
+
+        let selected: Vec<&DayPart> = day_parts
+            .iter()
+            .filter(|dp| days.contains(&dp.day))
+            .filter(|dp| {
+                if let Some(p) = part {
+                    dp.part == p
+                } else {
+                    true
+                }
             })
             .collect();
 
-        if body.is_empty() {
+        if selected.is_empty() {
             return Err("No matching day & part found".into());
         }
 
-        let gens = if args.is_present("generator") {
-            let mut parts: Vec<_> = matching_parts.clone().map(|dp| dp.part).collect();
-            parts.sort();
-            parts.dedup();
-
-            parts
-                .into_iter()
-                .map(|p| {
-                    let gen_name = format!("day{}", day.0);
-                    gen_tpl
-                        .replace("{GEN_NAME}", &gen_name)
-                        .replace("{DAY}", &day.0.to_string())
-                        .replace(
-                            "{IMPLS}",
-                            &matching_parts
-                                .clone()
-                                .filter(|dp| dp.part == p)
-                                .map(|dp| {
-                                    gen_impl_tpl
-                                        .replace(
-                                            "{RUNNER_NAME}",
-                                            &if let Some(n) = &dp.name {
-                                                format!(
-                                                    "day{}_part{}_{}",
-                                                    dp.day.0,
-                                                    dp.part.0,
-                                                    n.to_lowercase()
-                                                )
-                                            } else {
-                                                format!("day{}_part{}", dp.day.0, dp.part.0)
-                                            },
-                                        )
-                                        .replace("{DAY}", &dp.day.0.to_string())
-                                        .replace(
-                                            "{NAME}",
-                                            if let Some(n) = &dp.name {
-                                                n
-                                            } else {
-                                                "(default)"
-                                            },
-                                        )
-                                        .replace("{GEN_NAME}", &gen_name)
-                                })
-                                .collect::<String>(),
-                        )
-                })
-                .collect()
+        let mut inputs = Vec::new();
+        for &day in &days {
+            self.download_input(day, year)?;
+            let input_file = pm.input_file_for(year, day);
+            inputs.push((day, vec![(input_file.to_string(), input_file)]));
+        }
+
+        let answers: HashMap<DayPart, String> = self
+            .collect_answers(&Templates::load(&pm.crate_dir)?, &pm, &selected, &inputs)?
+            .into_iter()
+            .map(|a| (a.day_part, a.answer))
+            .collect();
+
+        let mut failures = 0;
+        for dp in selected {
+            let (_, display) = runner_names(dp);
+
+            match (answers.get(dp), store.get(dp.day, dp.part)) {
+                (None, _) => {
+                    println!("{} : {}", display, highlight("FAILED"));
+                    failures += 1;
+                }
+                (Some(answer), None) => println!("{} : {} (no known answer)", display, answer),
+                (Some(answer), Some(expected)) if answer == expected => {
+                    println!("{} : {} OK", display, answer)
+                }
+                (Some(answer), Some(expected)) => {
+                    println!(
+                        "{} : {} {}",
+                        display,
+                        answer,
+                        highlight(&format!("MISMATCH (expected {})", expected))
+                    );
+                    failures += 1;
+                }
+            }
+        }
+
+        if failures > 0 {
+            return Err(format!("{} solution(s) did not give the known answer", failures).into());
+        }
+
+        Ok(())
+    }
+
+    /// Runs the selected days & parts at the current revision and at another one,
+    /// and reports the answers that changed.
+    pub fn execute_diff(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        let rev = args.value_of("rev").expect("rev is required");
+
+        let day: Option<Day> = args
+            .value_of("day")
+            .map(|d| d.parse().expect("Failed to parse day"));
+
+        let part: Option<Part> = args
+            .value_of("part")
+            .map(|p| p.parse().expect("Failed to parse part"));
+
+        let pm = ProjectManager::new()?;
+
+        let day_parts = pm.build_project()?;
+        let year = day_parts.year;
+
+        let mut days: Vec<Day> = if args.is_present("all") {
+            day_parts.iter().map(|dp| dp.day).collect()
+        } else {
+            vec![day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day)]
+        };
+        days.dedup();
+
+        let mut inputs = Vec::new();
+        for &day in &days {
+            let files = self.input_files(
+                args,
+                day,
+                year,
+                pm.input_file_for(year, day),
+                &autobuild_dir(&pm).join("input"),
+            )?;
+            inputs.push((day, files));
+        }
+
+        let worktree = Worktree::checkout(&pm.crate_dir, rev)?;
+        let against_pm = pm.in_worktree(&worktree)?;
+        let against_parts = against_pm.build_project()?;
+
+        // `Option::is_none_or` needs Rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let select = |dp: &&DayPart| {
+            days.contains(&dp.day) && part.map_or(true, |p| dp.part == p)
+        };
+        let selected: Vec<&DayPart> = day_parts.iter().filter(select).collect();
+        let against_selected: Vec<&DayPart> = against_parts.iter().filter(select).collect();
+
+        if selected.is_empty() && against_selected.is_empty() {
+            return Err("No matching day & part found".into());
+        }
+
+        // Both revisions are generated with the current templates
+        let templates = Templates::load(&pm.crate_dir)?;
+        let answers = self.collect_answers(&templates, &pm, &selected, &inputs)?;
+        let against_answers = if against_selected.is_empty() {
+            Vec::new()
         } else {
-            String::new()
+            self.collect_answers(&templates, &against_pm, &against_selected, &inputs)?
         };
 
-        self.download_input(day, year)?;
-        let def_input_file = pm.input_file_for(year, day);
-
-        let main_content = bench_tpl
-            .replace("{CRATE_SLUG}", &pm.slug)
-            .replace("{PARTS}", &body)
-            .replace("{GENS}", &gens)
-            .replace(
-                "{BENCHMARKS}",
-                if args.is_present("generator") {
-                    "aoc_benchmark, input_benchmark"
+        let mut all_parts = selected.clone();
+        all_parts.extend(against_selected.iter().filter(|dp| !selected.contains(dp)));
+        all_parts.sort_by_key(|dp| (dp.day, dp.part, dp.name.clone()));
+
+        println!("Compared to {} ({}) :", rev, &worktree.commit[..12]);
+
+        let mut changes = 0;
+        for (day, files) in &inputs {
+            for (input, _) in files {
+                let label = if files.len() > 1 {
+                    format!(" [{}]", input)
                 } else {
-                    "aoc_benchmark"
-                },
-            )
-            .replace(
-                "{INPUTS}",
-                &template_input(
-                    day,
-                    year,
-                    args.value_of("input")
-                        .unwrap_or_else(|| def_input_file.as_str()),
-                )?,
-            );
+                    String::new()
+                };
+
+                let answer_in = |answers: &[Answer], dp: &DayPart| {
+                    answers
+                        .iter()
+                        .find(|a| &a.input == input && &a.day_part == dp)
+                        .map(|a| a.answer.clone())
+                };
+
+                for dp in all_parts.iter().filter(|dp| dp.day == *day) {
+                    let (_, display) = runner_names(dp);
+                    let now = answer_in(&answers, dp);
+                    let then = answer_in(&against_answers, dp);
+
+                    // A solution added or removed (or renamed) also changes the answers
+                    match (selected.contains(dp), against_selected.contains(dp)) {
+                        (true, false) => {
+                            println!(
+                                "{}{} : {} (new)",
+                                display,
+                                label,
+                                now.as_deref().unwrap_or("FAILED")
+                            );
+                            changes += 1;
+                        }
+                        (false, _) => {
+                            println!(
+                                "{}{} : {}",
+                                display,
+                                label,
+                                highlight(&format!(
+                                    "REMOVED (was {})",
+                                    then.as_deref().unwrap_or("FAILED")
+                                ))
+                            );
+                            changes += 1;
+                        }
+                        (true, true) if now == then => println!(
+                            "{}{} : {} unchanged",
+                            display,
+                            label,
+                            now.as_deref().unwrap_or("FAILED")
+                        ),
+                        (true, true) => {
+                            println!(
+                                "{}{} : {} {}",
+                                display,
+                                label,
+                                now.as_deref().unwrap_or("FAILED"),
+                                highlight(&format!(
+                                    "CHANGED (was {})",
+                                    then.as_deref().unwrap_or("FAILED")
+                                ))
+                            );
+                            changes += 1;
+                        }
+                    }
+                }
+            }
+        }
+
+        if changes > 0 {
+            return Err(format!("{} answer(s) changed since {}", changes, rev).into());
+        }
+
+        Ok(())
+    }
+
+    /// Runs every implementation of the selected days & parts, and checks that the named ones
+    /// give the same answer as the default one.
+    pub fn execute_check_agree(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        export_params(args)?;
+
+        let day: Option<Day> = args
+            .value_of("day")
+            .map(|d| d.parse().expect("Failed to parse day"));
+
+        let part: Option<Part> = args
+            .value_of("part")
+            .map(|p| p.parse().expect("Failed to parse part"));
+
+        let pm = ProjectManager::new()?;
+
+        let day_parts = pm.build_project()?;
+
+        let day = day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day);
+        let year = day_parts.year;
+
+        let selected: Vec<&DayPart> = day_parts
+            .iter()
+            .filter(|dp| dp.day == day)
+            .filter(|dp| {
+                if let Some(p) = part {
+                    dp.part == p
+                } else {
+                    true
+                }
+            })
+            .collect();
+
+        if selected.is_empty() {
+            return Err("No matching day & part found".into());
+        }
+
+        let inputs = self.input_files(
+            args,
+            day,
+            year,
+            pm.input_file_for(year, day),
+            &autobuild_dir(&pm).join("input"),
+        )?;
+
+        let answers = self.collect_answers(
+            &Templates::load(&pm.crate_dir)?,
+            &pm,
+            &selected,
+            &[(day, inputs.clone())],
+        )?;
+
+        let mut disagreements = 0;
+        for (input, _) in &inputs {
+            let label = if inputs.len() > 1 {
+                format!(" [{}]", input)
+            } else {
+                String::new()
+            };
+
+            let answer_of = |dp: &DayPart| {
+                answers
+                    .iter()
+                    .find(|a| &a.input == input && &a.day_part == dp)
+                    .map(|a| &a.answer)
+            };
+
+            for dp in &selected {
+                let (_, display) = runner_names(dp);
+
+                // Named implementations are compared against the default one,
+                // or against the first one if there is no default implementation.
+                let reference = selected
+                    .iter()
+                    .filter(|other| other.part == dp.part)
+                    .min_by_key(|other| other.name.is_some())
+                    .expect("a solution is its own reference");
+
+                match answer_of(dp) {
+                    None => {
+                        println!("{}{} : {}", display, label, highlight("FAILED"));
+                        disagreements += 1;
+                    }
+                    Some(answer) if reference == dp => println!("{}{} : {}", display, label, answer),
+                    Some(answer) if answer_of(reference) == Some(answer) => {
+                        println!("{}{} : {} agrees", display, label, answer)
+                    }
+                    Some(answer) => {
+                        let (_, reference_display) = runner_names(reference);
+                        println!(
+                            "{}{} : {} {}",
+                            display,
+                            label,
+                            answer,
+                            highlight(&format!(
+                                "DISAGREES with {} ({})",
+                                reference_display,
+                                answer_of(reference).map_or("FAILED", |a| a.as_str())
+                            ))
+                        );
+                        disagreements += 1;
+                    }
+                }
+            }
+        }
+
+        if disagreements > 0 {
+            return Err(format!("{} solution(s) disagree", disagreements).into());
+        }
+
+        Ok(())
+    }
+
+    pub fn execute_bench(&self, args: &ArgMatches) -> Result<(), Box<dyn error::Error>> {
+        export_params(args)?;
+
+        let day: Option<Day> = args
+            .value_of("day")
+            .map(|d| d.parse().expect("Failed to parse day"));
+
+        let part: Option<Part> = args
+            .value_of("part")
+            .map(|p| p.parse().expect("Failed to parse part"));
+
+        let pm = ProjectManager::new()?;
+
+        let day_parts = pm.build_project()?;
+
+        let mut days: Vec<Day> = if args.is_present("all") {
+            day_parts.iter().map(|dp| dp.day).collect()
+        } else {
+            vec![day.unwrap_or_else(|| day_parts.last().expect("No implementation found").day)]
+        };
+        days.dedup();
+        let year = day_parts.year;
 
         let autobench_dir = pm
             .root_target_dir
             .join("aoc")
-            .join(pm.slug)
+            .join(&pm.slug)
             .join("aoc-autobench");
 
-        fs::create_dir_all(autobench_dir.join("benches"))
-            .expect("failed to create autobench directory");
-        fs::write(autobench_dir.join("Cargo.toml"), &cargo_content)
-            .expect("failed to write Cargo.toml");
-        fs::write(
-            autobench_dir.join("benches/aoc_benchmark.rs"),
-            &main_content,
-        )
-        .expect("failed to write benches/aoc_benchmark.rs");
+        let mut inputs = Vec::new();
+        for &day in &days {
+            let files = self.input_files(
+                args,
+                day,
+                year,
+                pm.input_file_for(year, day),
+                &autobench_dir.join("input"),
+            )?;
+            inputs.push((day, files));
+        }
+        let bench_inputs: Vec<(Day, Utf8PathBuf)> = inputs
+            .iter()
+            .map(|(day, files)| (*day, files[0].1.clone()))
+            .collect();
 
-        let status = process::Command::new("cargo")
-            .args(&["bench"])
-            .current_dir(&autobench_dir)
-            .spawn()
-            .expect("Failed to run cargo")
-            .wait()
-            .expect("Failed to wait for cargo");
+        if args.is_present("cachegrind") {
+            return self.bench_cachegrind(args, &pm, &day_parts, part, &inputs);
+        }
+
+        let templates = Templates::load(&pm.crate_dir)?;
+        write_autobench(&templates, &pm, &autobench_dir, args, &day_parts, part, &inputs)?;
 
+        let start_time = SystemTime::now();
+
+        let status = run_bench(&autobench_dir);
         if !status.success() {
             process::exit(status.code().unwrap_or(-1));
         }
 
+        let results = criterion::read_results(&autobench_dir.join("target/criterion"), start_time)?;
+        if !results.is_empty() {
+            let summary = criterion::summary_table(&results);
+            let summary_file = autobench_dir.join("summary.md");
+            fs::write(&summary_file, &summary)?;
+
+            let profile = if args.is_present("profile") {
+                "bench (debug info)"
+            } else {
+                "bench"
+            };
+            let env = Environment::collect(&pm.crate_dir, profile, &bench_inputs);
+            fs::write(
+                autobench_dir.join("results.json"),
+                criterion::export_json(&results, &env),
+            )?;
+            fs::write(
+                autobench_dir.join("results.csv"),
+                criterion::export_csv(&results, &env),
+            )?;
+
+            println!(
+                "\n{}\nSummary written to {}, full results to results.json & results.csv",
+                summary, summary_file
+            );
+
+            let history_file = args.value_of("history").map(Utf8PathBuf::from).unwrap_or_else(|| {
+                pm.root_target_dir
+                    .join("aoc")
+                    .join(&pm.slug)
+                    .join("bench-history.json")
+            });
+
+            let mut history = History::load(history_file)?;
+            let baseline = history.baseline(&env);
+            history.record(&results, &env);
+            history.save()?;
+
+            if let Some(rev) = args.value_of("against") {
+                let worktree = Worktree::checkout(&pm.crate_dir, rev)?;
+                let against_pm = pm.in_worktree(&worktree)?;
+                let against_parts = against_pm.build_project()?;
+                let against_dir = pm
+                    .root_target_dir
+                    .join("aoc")
+                    .join(&pm.slug)
+                    .join("aoc-autobench-against");
+
+                write_autobench(
+                    &templates,
+                    &against_pm,
+                    &against_dir,
+                    args,
+                    &against_parts,
+                    part,
+                    &inputs,
+                )
+                .map_err(|e| format!("{} at {}", e, rev))?;
+
+                let start_time = SystemTime::now();
+                if !run_bench(&against_dir).success() {
+                    return Err(format!("Benchmarks failed at {}", rev).into());
+                }
+
+                let against_results =
+                    criterion::read_results(&against_dir.join("target/criterion"), start_time)?;
+                println!(
+                    "\nCompared to {} ({}) :\n\n{}",
+                    rev,
+                    &worktree.commit[..12],
+                    criterion::comparison_table(&against_results, &results, rev)
+                );
+            }
+
+            if args.is_present("compare") {
+                let threshold: f64 = args
+                    .value_of("threshold")
+                    .unwrap_or("5")
+                    .parse()
+                    .expect("Failed to parse threshold");
+
+                match baseline {
+                    Some(baseline) => {
+                        println!("\nCompared to {} :", baseline.revision);
+
+                        let regressions = history::compare(&results, &baseline, threshold);
+                        if regressions > 0 {
+                            return Err(format!(
+                                "{} benchmark(s) regressed by more than {}%",
+                                regressions, threshold
+                            )
+                            .into());
+                        }
+                    }
+                    None => println!(
+                        "\nNo results from another revision on this machine to compare to"
+                    ),
+                }
+            }
+        }
+
         if args.is_present("open") {
             let index = autobench_dir.join("target/criterion/report/index.html");
 
@@ -503,14 +1223,285 @@ impl AOCApp {
     }
 }
 
-fn template_input(day: Day, _year: u32, input: &str) -> Result<String, Box<dyn std::error::Error>> {
-    let path = Utf8PathBuf::from_path_buf(std::fs::canonicalize(input)?)
-        .map_err(|e| format!("Non unicode path: {}", e.display()))?;
+fn autobuild_dir(pm: &ProjectManager) -> Utf8PathBuf {
+    pm.root_target_dir
+        .join("aoc")
+        .join(&pm.slug)
+        .join("aoc-autobuild")
+}
+
+/// Writes the autobench crate of `pm`, benchmarking the solutions of `day_parts` on `inputs`
+fn write_autobench(
+    templates: &Templates,
+    pm: &ProjectManager,
+    autobench_dir: &Utf8Path,
+    args: &ArgMatches,
+    day_parts: &DayParts,
+    part: Option<Part>,
+    inputs: &[(Day, Vec<(String, Utf8PathBuf)>)],
+) -> Result<(), Box<dyn error::Error>> {
+    let cargo_content = templates.render(
+        "Cargo-bench.toml.tpl",
+        &manifest(pm, args.is_present("profile")),
+    )?;
+
+    let mut body = String::new();
+    let mut gens = String::new();
+    let mut templated_inputs = String::new();
+
+    let end_to_end = args.is_present("end-to-end");
+    let impl_tpl = if end_to_end {
+        "benches/e2e_impl.rs.tpl"
+    } else {
+        "benches/impl.rs.tpl"
+    };
+
+    for (day, files) in inputs {
+        let day = *day;
+        // `Option::is_none_or` needs Rust 1.82
+        #[allow(clippy::unnecessary_map_or)]
+        let matching_parts: Vec<&DayPart> = day_parts
+            .iter()
+            .filter(|dp| dp.day == day)
+            .filter(|dp| part.map_or(true, |p| dp.part == p))
+            .collect();
 
-    let tera = get_tera();
-    let mut ctx = tera::Context::new();
-    ctx.insert("PATH", &path);
-    ctx.insert("DAY", &day.0);
+        let mut parts: Vec<_> = matching_parts.iter().map(|dp| dp.part).collect();
+        parts.sort();
+        parts.dedup();
+
+        for p in parts {
+            let part_name = format!("day{}_part{}", day.0, p.0);
+            let impls = matching_parts
+                .iter()
+                .filter(|dp| dp.part == p)
+                .map(|dp| {
+                    templates.render(
+                        impl_tpl,
+                        &BenchImpl {
+                            part_name: &part_name,
+                            day: dp.day.0,
+                            name: dp.name.as_deref().unwrap_or("(default)"),
+                            runner_name: runner_names(dp).0,
+                        },
+                    )
+                })
+                .collect::<Result<String, _>>()?;
+
+            body += &templates.render(
+                "benches/part.rs.tpl",
+                &BenchPart {
+                    part_name: &part_name,
+                    day: day.0,
+                    part: p.0,
+                    total: end_to_end,
+                    impls,
+                },
+            )?;
+        }
+
+        if args.is_present("generator") {
+            // Solutions sharing a generator (usually both parts of a day) only benchmark it once,
+            // named after the generator function when it is known (or its path, when the name is taken).
+            let mut generators: Vec<(&str, String, &DayPart)> = Vec::new();
+            for dp in &matching_parts {
+                let (key, name) = match day_parts.generator(dp) {
+                    Some(generator) => (generator.path.as_str(), generator.name.as_str()),
+                    None => {
+                        let name = dp.name.as_deref().unwrap_or("(default)");
+                        (name, name)
+                    }
+                };
+
+                if generators.iter().any(|(k, _, _)| *k == key) {
+                    continue;
+                }
+
+                let name = if generators.iter().any(|(_, n, _)| n == name) {
+                    key
+                } else {
+                    name
+                };
+                generators.push((key, name.to_string(), dp));
+            }
+
+            let gen_name = format!("day{}", day.0);
+            let impls = generators
+                .iter()
+                .map(|(_, name, dp)| {
+                    templates.render(
+                        "benches/gen_impl.rs.tpl",
+                        &BenchGenImpl {
+                            gen_name: &gen_name,
+                            day: dp.day.0,
+                            name,
+                            runner_name: runner_names(dp).0,
+                        },
+                    )
+                })
+                .collect::<Result<String, _>>()?;
+
+            gens += &templates.render(
+                "benches/gen.rs.tpl",
+                &BenchGen {
+                    gen_name: &gen_name,
+                    day: day.0,
+                    impls,
+                },
+            )?;
+        }
+
+        templated_inputs += &template_input(templates, day, files)?;
+    }
+
+    if body.is_empty() {
+        return Err("No matching day & part found".into());
+    }
+
+    let main_content = templates.render(
+        "benches/aoc_benchmark.rs.tpl",
+        &BenchMain {
+            crate_slug: &pm.slug,
+            inputs: templated_inputs,
+            parts: body,
+            gens,
+            generators: args.is_present("generator"),
+        },
+    )?;
+
+    fs::create_dir_all(autobench_dir.join("benches"))
+        .expect("failed to create autobench directory");
+    fs::write(autobench_dir.join("Cargo.toml"), &cargo_content)
+        .expect("failed to write Cargo.toml");
+    fs::write(
+        autobench_dir.join("benches/aoc_benchmark.rs"),
+        &main_content,
+    )
+    .expect("failed to write benches/aoc_benchmark.rs");
+
+    Ok(())
+}
+
+/// Environment variable read by `aoc-runner` to override the params of the solutions
+const PARAMS_ENV: &str = "AOC_PARAMS";
+
+/// Passes the `--param` overrides to the solutions we run, through our environment.
+///
+/// Returns the value of the environment variable, if any.
+fn export_params(args: &ArgMatches) -> Result<Option<String>, Box<dyn error::Error>> {
+    let params = match args.values_of("param") {
+        Some(params) => params.collect::<Vec<_>>(),
+        None => return Ok(None),
+    };
+
+    if let Some(param) = params.iter().find(|p| !p.contains('=') || p.contains(',')) {
+        return Err(format!("Invalid param `{}`, expected NAME=VALUE", param).into());
+    }
+
+    let params = params.join(",");
+    std::env::set_var(PARAMS_ENV, &params);
+
+    Ok(Some(params))
+}
+
+/// Runs the benchmarks of an autobench crate
+fn run_bench(autobench_dir: &Utf8Path) -> process::ExitStatus {
+    process::Command::new("cargo")
+        .args(["bench"])
+        .current_dir(autobench_dir)
+        .spawn()
+        .expect("Failed to run cargo")
+        .wait()
+        .expect("Failed to wait for cargo")
+}
+
+/// Writes the autobuild crate, whose `src/main.rs` is `main_content`
+pub fn write_autobuild(
+    templates: &Templates,
+    pm: &ProjectManager,
+    autobuild_dir: &Utf8Path,
+    profile: bool,
+    main_content: &str,
+) -> Result<(), Box<dyn error::Error>> {
+    let cargo_content = templates.render("Cargo-run.toml.tpl", &manifest(pm, profile))?;
+
+    fs::create_dir_all(autobuild_dir.join("src")).expect("failed to create autobuild directory");
+    fs::write(autobuild_dir.join("Cargo.toml"), &cargo_content)
+        .expect("failed to write Cargo.toml");
+    fs::write(autobuild_dir.join("src/main.rs"), main_content)
+        .expect("failed to write src/main.rs");
+
+    Ok(())
+}
+
+fn manifest(pm: &ProjectManager, debug_info: bool) -> Manifest<'_> {
+    Manifest {
+        crate_name: &pm.name,
+        crate_path: pm.crate_dir.as_str().escape_default().to_string(),
+        debug_info,
+    }
+}
+
+/// Makes a message stand out, when printed to a terminal
+fn highlight(message: &str) -> String {
+    if io::stdout().is_terminal() {
+        format!("\x1b[1;31m{}\x1b[0m", message)
+    } else {
+        message.to_string()
+    }
+}
+
+/// Gets the name of the `Factory` method of a solution, and how to display it
+fn runner_names(dp: &DayPart) -> (String, String) {
+    if let Some(n) = &dp.name {
+        (
+            format!("day{}_part{}_{}", dp.day.0, dp.part.0, n.to_lowercase()),
+            format!("Day {} - Part {} - {}", dp.day.0, dp.part.0, n),
+        )
+    } else {
+        (
+            format!("day{}_part{}", dp.day.0, dp.part.0),
+            format!("Day {} - Part {}", dp.day.0, dp.part.0),
+        )
+    }
+}
+
+/// Gets the context of the templates generating code for a single solution
+fn solution(dp: &DayPart) -> Solution<'_> {
+    let (runner_name, runner_display) = runner_names(dp);
+
+    Solution {
+        day: dp.day.0,
+        part: dp.part.0,
+        name: dp.name.as_deref().unwrap_or(""),
+        runner_name,
+        runner_display,
+        generator: None,
+        both: None,
+    }
+}
+
+fn template_input(
+    templates: &Templates,
+    day: Day,
+    inputs: &[(String, Utf8PathBuf)],
+) -> Result<String, Box<dyn std::error::Error>> {
+    let mut templated = Vec::new();
+    for (name, input) in inputs {
+        let path = Utf8PathBuf::from_path_buf(std::fs::canonicalize(input)?)
+            .map_err(|e| format!("Non unicode path: {}", e.display()))?;
+
+        templated.push(Input {
+            name: name.escape_default().to_string(),
+            path: path.as_str().escape_default().to_string(),
+        });
+    }
 
-    tera.render("input.rs.tpl", &ctx).map_err(|e| e.into())
+    templates.render(
+        "input.rs.tpl",
+        &Inputs {
+            day: day.0,
+            inputs: templated,
+        },
+    )
 }
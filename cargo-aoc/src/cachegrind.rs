@@ -0,0 +1,187 @@
+use camino::Utf8Path;
+use serde_json::json;
+use std::error;
+use std::fs;
+
+/// Instruction & cache counts of a solution, read from Cachegrind's output
+pub struct CachegrindResult {
+    pub day: u8,
+    pub part: u8,
+    pub name: String,
+    pub counts: Counts,
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Counts {
+    pub instructions: u64,
+    pub l1_misses: u64,
+    pub ll_misses: u64,
+    pub estimated_cycles: u64,
+}
+
+impl Counts {
+    /// Reads the summary of a `cachegrind.out` file.
+    ///
+    /// The cycles are estimated the way iai does, from the hits at each cache level.
+    pub fn read(file: &Utf8Path) -> Result<Counts, Box<dyn error::Error>> {
+        let content = fs::read_to_string(file)?;
+
+        let events: Vec<&str> = content
+            .lines()
+            .find_map(|l| l.strip_prefix("events:"))
+            .ok_or_else(|| format!("{}: no events", file))?
+            .split_whitespace()
+            .collect();
+        let summary: Vec<u64> = content
+            .lines()
+            .find_map(|l| l.strip_prefix("summary:"))
+            .ok_or_else(|| format!("{}: no summary", file))?
+            .split_whitespace()
+            .map(|n| n.parse())
+            .collect::<Result<_, _>>()?;
+
+        let event = |name: &str| {
+            events
+                .iter()
+                .position(|e| *e == name)
+                .and_then(|i| summary.get(i).copied())
+                .unwrap_or(0)
+        };
+
+        let accesses = event("Ir") + event("Dr") + event("Dw");
+        let l1_misses = event("I1mr") + event("D1mr") + event("D1mw");
+        let ll_misses = event("ILmr") + event("DLmr") + event("DLmw");
+
+        Ok(Counts {
+            instructions: event("Ir"),
+            l1_misses,
+            ll_misses,
+            estimated_cycles: (accesses - l1_misses) + 5 * (l1_misses - ll_misses) + 35 * ll_misses,
+        })
+    }
+
+    /// Removes the counts of a calibration run (startup & generator) from the counts of a full run
+    pub fn without(self, calibration: Counts) -> Counts {
+        Counts {
+            instructions: self.instructions.saturating_sub(calibration.instructions),
+            l1_misses: self.l1_misses.saturating_sub(calibration.l1_misses),
+            ll_misses: self.ll_misses.saturating_sub(calibration.ll_misses),
+            estimated_cycles: self
+                .estimated_cycles
+                .saturating_sub(calibration.estimated_cycles),
+        }
+    }
+}
+
+impl CachegrindResult {
+    pub fn display(&self) -> String {
+        let mut display = format!("Day {} - Part {}", self.day, self.part);
+
+        if self.name != "(default)" {
+            display += " - ";
+            display += &self.name;
+        }
+
+        display
+    }
+
+    fn is_same_solution(&self, other: &CachegrindResult) -> bool {
+        self.day == other.day && self.part == other.part && self.name == other.name
+    }
+
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "day": self.day,
+            "part": self.part,
+            "implementation": self.name,
+            "instructions": self.counts.instructions,
+            "l1_misses": self.counts.l1_misses,
+            "ll_misses": self.counts.ll_misses,
+            "estimated_cycles": self.counts.estimated_cycles,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<CachegrindResult> {
+        Some(CachegrindResult {
+            day: value["day"].as_u64()? as u8,
+            part: value["part"].as_u64()? as u8,
+            name: value["implementation"].as_str()?.to_string(),
+            counts: Counts {
+                instructions: value["instructions"].as_u64()?,
+                l1_misses: value["l1_misses"].as_u64()?,
+                ll_misses: value["ll_misses"].as_u64()?,
+                estimated_cycles: value["estimated_cycles"].as_u64()?,
+            },
+        })
+    }
+}
+
+/// Reads the results saved by `save`, or nothing if there is none yet
+pub fn load(file: &Utf8Path) -> Result<Vec<CachegrindResult>, Box<dyn error::Error>> {
+    if !file.exists() {
+        return Ok(Vec::new());
+    }
+
+    let values: Vec<serde_json::Value> = serde_json::from_str(&fs::read_to_string(file)?)?;
+    Ok(values.iter().filter_map(CachegrindResult::from_json).collect())
+}
+
+/// Saves the results, keeping the previous ones of solutions that were not run this time
+pub fn save(
+    file: &Utf8Path,
+    results: &[CachegrindResult],
+    previous: Vec<CachegrindResult>,
+) -> Result<(), Box<dyn error::Error>> {
+    let values: Vec<serde_json::Value> = results
+        .iter()
+        .chain(
+            previous
+                .iter()
+                .filter(|p| !results.iter().any(|r| r.is_same_solution(p))),
+        )
+        .map(CachegrindResult::to_json)
+        .collect();
+
+    fs::write(file, serde_json::to_string_pretty(&values)?)?;
+
+    Ok(())
+}
+
+/// Prints the counts of each solution, along with their change since the previous run,
+/// and returns how many solutions executed more than `threshold` percents more instructions.
+pub fn report(results: &[CachegrindResult], previous: &[CachegrindResult], threshold: f64) -> usize {
+    let mut regressions = 0;
+
+    for result in results {
+        let before = previous.iter().find(|p| p.is_same_solution(result));
+        let change = |now: u64, before: Option<u64>| match before {
+            Some(before) if before > 0 => {
+                format!(" ({:+.2}%)", (now as f64 - before as f64) / before as f64 * 100.0)
+            }
+            _ => String::new(),
+        };
+
+        let counts = &result.counts;
+        println!(
+            "{}\n\tinstructions: {}{}\n\tL1 misses: {}{}\n\tLL misses: {}{}\n\testimated cycles: {}{}\n",
+            result.display(),
+            counts.instructions,
+            change(counts.instructions, before.map(|b| b.counts.instructions)),
+            counts.l1_misses,
+            change(counts.l1_misses, before.map(|b| b.counts.l1_misses)),
+            counts.ll_misses,
+            change(counts.ll_misses, before.map(|b| b.counts.ll_misses)),
+            counts.estimated_cycles,
+            change(counts.estimated_cycles, before.map(|b| b.counts.estimated_cycles)),
+        );
+
+        if let Some(before) = before {
+            let before = before.counts.instructions as f64;
+            if before > 0.0 && (counts.instructions as f64 - before) / before * 100.0 > threshold {
+                regressions += 1;
+            }
+        }
+    }
+
+    regressions
+}
@@ -0,0 +1,328 @@
+use crate::environment::Environment;
+use camino::Utf8Path;
+use serde_json::json;
+use std::error;
+use std::fs;
+use std::time::SystemTime;
+
+/// Result of a benchmark, read from Criterion's output
+pub struct BenchResult {
+    pub day: u8,
+    /// `1`, `2`, `1 (total)`, `2 (total)` or `Generator`
+    pub part: String,
+    pub name: String,
+    pub median: Estimate,
+    pub mean: Estimate,
+    pub std_dev: Estimate,
+}
+
+/// A statistic estimated by Criterion, in nanoseconds
+pub struct Estimate {
+    pub point: f64,
+    pub lower_bound: f64,
+    pub upper_bound: f64,
+}
+
+impl Estimate {
+    fn to_json(&self) -> serde_json::Value {
+        json!({
+            "point_estimate": self.point,
+            "lower_bound": self.lower_bound,
+            "upper_bound": self.upper_bound,
+        })
+    }
+
+    fn from_json(value: &serde_json::Value) -> Option<Estimate> {
+        Some(Estimate {
+            point: value["point_estimate"].as_f64()?,
+            lower_bound: value["lower_bound"].as_f64()?,
+            upper_bound: value["upper_bound"].as_f64()?,
+        })
+    }
+}
+
+impl BenchResult {
+    /// Displays the benchmark like `Day 1 - Part 2 - Fnv`
+    pub fn display(&self) -> String {
+        let mut display = if self.part.starts_with(|c: char| c.is_ascii_digit()) {
+            format!("Day {} - Part {}", self.day, self.part)
+        } else {
+            format!("Day {} - {}", self.day, self.part)
+        };
+
+        if self.name != "(default)" {
+            display += " - ";
+            display += &self.name;
+        }
+
+        display
+    }
+
+    /// Whether both results are of the same benchmark, possibly from different runs
+    pub fn is_same_benchmark(&self, other: &BenchResult) -> bool {
+        self.day == other.day && self.part == other.part && self.name == other.name
+    }
+
+    /// Reads back a benchmark exported by `to_json`
+    pub fn from_json(value: &serde_json::Value) -> Option<BenchResult> {
+        Some(BenchResult {
+            day: value["day"].as_u64()? as u8,
+            part: value["part"].as_str()?.to_string(),
+            name: value["implementation"].as_str()?.to_string(),
+            median: Estimate::from_json(&value["median"])?,
+            mean: Estimate::from_json(&value["mean"])?,
+            std_dev: Estimate::from_json(&value["std_dev"])?,
+        })
+    }
+}
+
+/// Reads the results of the benchmarks written by Criterion since `since`
+///
+/// Each benchmark has a `new/` directory holding a `benchmark.json` (its ids) and
+/// an `estimates.json` (its statistics).
+pub fn read_results(
+    criterion_dir: &Utf8Path,
+    since: SystemTime,
+) -> Result<Vec<BenchResult>, Box<dyn error::Error>> {
+    let mut results = Vec::new();
+    let mut dirs = vec![criterion_dir.to_path_buf()];
+
+    while let Some(dir) = dirs.pop() {
+        let new_dir = dir.join("new");
+        let estimates_file = new_dir.join("estimates.json");
+
+        if estimates_file.exists() && fs::metadata(&estimates_file)?.modified()? >= since {
+            let benchmark: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(new_dir.join("benchmark.json"))?)?;
+            let estimates: serde_json::Value =
+                serde_json::from_str(&fs::read_to_string(&estimates_file)?)?;
+
+            if let Some(result) = to_result(&benchmark, &estimates) {
+                results.push(result);
+            }
+        }
+
+        for entry in dir.read_dir_utf8()? {
+            let entry = entry?;
+            if entry.file_type()?.is_dir() && entry.file_name() != "new" {
+                dirs.push(entry.path().to_path_buf());
+            }
+        }
+    }
+
+    results.sort_by(|a, b| {
+        (a.day, &a.part, a.name != "(default)", &a.name).cmp(&(
+            b.day,
+            &b.part,
+            b.name != "(default)",
+            &b.name,
+        ))
+    });
+
+    Ok(results)
+}
+
+fn to_result(benchmark: &serde_json::Value, estimates: &serde_json::Value) -> Option<BenchResult> {
+    let group = benchmark["group_id"].as_str()?;
+    let name = benchmark["function_id"].as_str()?.to_string();
+
+    // Groups are named `Day{DAY} - Part{PART}`, `Day{DAY} - Part{PART} (total)`
+    // or `Generator Day{DAY}`
+    let (day, part) = if let Some(day) = group.strip_prefix("Generator Day") {
+        (day, "Generator".to_string())
+    } else {
+        let mut split = group.strip_prefix("Day")?.splitn(2, " - ");
+        let day = split.next()?;
+        (day, split.next()?.trim_start_matches("Part").to_string())
+    };
+
+    // Criterion 0.2 capitalizes statistics' names, 0.3 onwards do not
+    let estimate = |stat: &str| {
+        let e = estimates
+            .get(stat)
+            .or_else(|| estimates.get(stat.to_lowercase()))?;
+
+        Some(Estimate {
+            point: e["point_estimate"].as_f64()?,
+            lower_bound: e["confidence_interval"]["lower_bound"].as_f64()?,
+            upper_bound: e["confidence_interval"]["upper_bound"].as_f64()?,
+        })
+    };
+
+    Some(BenchResult {
+        day: day.parse().ok()?,
+        part,
+        name,
+        median: estimate("Median")?,
+        mean: estimate("Mean")?,
+        std_dev: estimate("StdDev")?,
+    })
+}
+
+/// Formats a duration in nanoseconds the way Criterion does
+pub fn format_time(ns: f64) -> String {
+    let short = |n: f64| {
+        if n < 10.0 {
+            format!("{:.4}", n)
+        } else if n < 100.0 {
+            format!("{:.3}", n)
+        } else {
+            format!("{:.2}", n)
+        }
+    };
+
+    if ns < 1e3 {
+        format!("{} ns", short(ns))
+    } else if ns < 1e6 {
+        format!("{} µs", short(ns / 1e3))
+    } else if ns < 1e9 {
+        format!("{} ms", short(ns / 1e6))
+    } else {
+        format!("{} s", short(ns / 1e9))
+    }
+}
+
+/// Builds a Markdown table of the results, ready to be pasted in a README
+pub fn summary_table(results: &[BenchResult]) -> String {
+    let mut table = String::from("| Day | Part | Implementation | Median time |\n");
+    table += "|----:|------|----------------|------------:|\n";
+
+    for result in results {
+        table += &format!(
+            "| {} | {} | {} | {} |\n",
+            result.day,
+            result.part,
+            result.name,
+            format_time(result.median.point)
+        );
+    }
+
+    table
+}
+
+/// Builds a Markdown table comparing the results of the `base_name` revision to the current ones
+pub fn comparison_table(base: &[BenchResult], current: &[BenchResult], base_name: &str) -> String {
+    let mut table = format!(
+        "| Day | Part | Implementation | {} | Current | Speedup |\n",
+        base_name
+    );
+    table += "|----:|------|----------------|----:|--------:|--------:|\n";
+
+    for result in current {
+        let (base_time, speedup) = match base.iter().find(|b| b.is_same_benchmark(result)) {
+            Some(b) => (
+                format_time(b.median.point),
+                format!("x{:.2}", b.median.point / result.median.point),
+            ),
+            None => ("-".to_string(), "-".to_string()),
+        };
+
+        table += &format!(
+            "| {} | {} | {} | {} | {} | {} |\n",
+            result.day,
+            result.part,
+            result.name,
+            base_time,
+            format_time(result.median.point),
+            speedup
+        );
+    }
+
+    for b in base
+        .iter()
+        .filter(|b| !current.iter().any(|r| r.is_same_benchmark(b)))
+    {
+        table += &format!(
+            "| {} | {} | {} | {} | - | - |\n",
+            b.day,
+            b.part,
+            b.name,
+            format_time(b.median.point)
+        );
+    }
+
+    table
+}
+
+/// Exports the results as JSON, along with the environment that produced them
+pub fn export_json(results: &[BenchResult], env: &Environment) -> String {
+    serde_json::to_string_pretty(&to_json(results, env))
+        .expect("failed to serialize benchmark results")
+}
+
+pub fn to_json(results: &[BenchResult], env: &Environment) -> serde_json::Value {
+    let benchmarks: Vec<serde_json::Value> = results
+        .iter()
+        .map(|result| {
+            json!({
+                "day": result.day,
+                "part": result.part,
+                "implementation": result.name,
+                "input_hash": env.input_hash(result.day),
+                "median": result.median.to_json(),
+                "mean": result.mean.to_json(),
+                "std_dev": result.std_dev.to_json(),
+            })
+        })
+        .collect();
+
+    let inputs: serde_json::Map<String, serde_json::Value> = env
+        .input_hashes
+        .iter()
+        .map(|(day, hash)| (format!("day{}", day.0), json!(hash)))
+        .collect();
+
+    json!({
+        "environment": {
+            "git_commit": env.git_commit,
+            "rustc_version": env.rustc_version,
+            "cpu_model": env.cpu_model,
+            "profile": env.profile,
+            "input_hashes": inputs,
+        },
+        "benchmarks": benchmarks,
+    })
+}
+
+/// Exports the results as CSV, one benchmark per line, repeating the environment on each line
+pub fn export_csv(results: &[BenchResult], env: &Environment) -> String {
+    let mut csv = String::from(
+        "day,part,implementation,median_ns,median_lower_ns,median_upper_ns,\
+         mean_ns,mean_lower_ns,mean_upper_ns,std_dev_ns,\
+         input_hash,git_commit,rustc_version,cpu_model,profile\n",
+    );
+
+    for result in results {
+        let fields = [
+            result.day.to_string(),
+            result.part.clone(),
+            result.name.clone(),
+            result.median.point.to_string(),
+            result.median.lower_bound.to_string(),
+            result.median.upper_bound.to_string(),
+            result.mean.point.to_string(),
+            result.mean.lower_bound.to_string(),
+            result.mean.upper_bound.to_string(),
+            result.std_dev.point.to_string(),
+            env.input_hash(result.day).unwrap_or("").to_string(),
+            env.git_commit.clone().unwrap_or_default(),
+            env.rustc_version.clone().unwrap_or_default(),
+            env.cpu_model.clone().unwrap_or_default(),
+            env.profile.clone(),
+        ];
+
+        let line: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        csv += &line.join(",");
+        csv.push('\n');
+    }
+
+    csv
+}
+
+fn csv_field(field: &str) -> String {
+    if field.contains(&[',', '"', '\n'][..]) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
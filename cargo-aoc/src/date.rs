@@ -44,4 +44,9 @@ impl AOCDate {
             self.year, self.day
         )
     }
+
+    /// Gets the URL of the puzzle's page
+    pub fn puzzle_url(&self) -> String {
+        format!("https://adventofcode.com/{}/day/{}", self.year, self.day)
+    }
 }
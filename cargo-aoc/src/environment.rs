@@ -0,0 +1,88 @@
+use aoc_runner_internal::Day;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::fs;
+use std::process;
+
+/// What produced a set of benchmark results
+pub struct Environment {
+    /// Commit of the solutions, suffixed with `-dirty` if there are uncommitted changes
+    pub git_commit: Option<String>,
+    pub rustc_version: Option<String>,
+    pub cpu_model: Option<String>,
+    pub profile: String,
+    /// Hash of the input of each day, to know whether results were produced on the same inputs
+    pub input_hashes: Vec<(Day, String)>,
+}
+
+impl Environment {
+    pub fn collect(crate_dir: &Utf8Path, profile: &str, inputs: &[(Day, Utf8PathBuf)]) -> Self {
+        let git_commit = command_output(crate_dir, "git", &["rev-parse", "HEAD"]).map(|commit| {
+            match command_output(crate_dir, "git", &["status", "--porcelain"]) {
+                Some(status) if !status.is_empty() => format!("{}-dirty", commit),
+                _ => commit,
+            }
+        });
+
+        let input_hashes = inputs
+            .iter()
+            .filter_map(|(day, path)| fs::read(path).ok().map(|content| (*day, hash(&content))))
+            .collect();
+
+        Environment {
+            git_commit,
+            rustc_version: command_output(crate_dir, "rustc", &["--version"]),
+            cpu_model: cpu_model(),
+            profile: profile.to_string(),
+            input_hashes,
+        }
+    }
+
+    pub fn input_hash(&self, day: u8) -> Option<&str> {
+        self.input_hashes
+            .iter()
+            .find(|(d, _)| d.0 == day)
+            .map(|(_, hash)| hash.as_str())
+    }
+}
+
+/// Runs a command, returning its trimmed output if it succeeded
+fn command_output(dir: &Utf8Path, cmd: &str, args: &[&str]) -> Option<String> {
+    let output = process::Command::new(cmd)
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .ok()?;
+
+    if output.status.success() {
+        String::from_utf8(output.stdout)
+            .ok()
+            .map(|out| out.trim().to_string())
+    } else {
+        None
+    }
+}
+
+fn cpu_model() -> Option<String> {
+    if let Ok(cpuinfo) = fs::read_to_string("/proc/cpuinfo") {
+        cpuinfo
+            .lines()
+            .find(|l| l.starts_with("model name"))
+            .and_then(|l| l.split_once(':'))
+            .map(|(_, model)| model.trim().to_string())
+    } else {
+        command_output(
+            Utf8Path::new("."),
+            "sysctl",
+            &["-n", "machdep.cpu.brand_string"],
+        )
+    }
+}
+
+/// 64-bit FNV-1a, which is stable across platforms & versions (unlike `DefaultHasher`)
+fn hash(content: &[u8]) -> String {
+    let hash = content.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+
+    format!("{:016x}", hash)
+}
@@ -0,0 +1,259 @@
+use crate::criterion::{self, format_time, BenchResult};
+use crate::environment::Environment;
+use camino::Utf8PathBuf;
+use std::error;
+use std::fs;
+
+/// Results recorded on another revision, to compare the current ones with
+pub struct Baseline {
+    pub revision: String,
+    /// Results recorded over the same input as the current ones
+    pub results: Vec<BenchResult>,
+    /// Results recorded over another input, that can't be compared
+    pub other_inputs: Vec<BenchResult>,
+}
+
+/// Benchmark results of past runs, keyed by the git revision they were run on
+///
+/// Stored as a JSON array of the exports of `criterion::to_json`, oldest first.
+pub struct History {
+    path: Utf8PathBuf,
+    entries: Vec<serde_json::Value>,
+}
+
+impl History {
+    pub fn load(path: Utf8PathBuf) -> Result<Self, Box<dyn error::Error>> {
+        let entries = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            Vec::new()
+        };
+
+        Ok(History { path, entries })
+    }
+
+    /// Gets the latest results recorded on another revision than the one of `env`, on the same machine
+    /// and with the same profile, so that the timings are comparable.
+    ///
+    /// Results recorded over another input than the current one are set apart, as they can't be compared.
+    pub fn baseline(&self, env: &Environment) -> Option<Baseline> {
+        let revision = env.git_commit.as_deref().unwrap_or("unknown");
+
+        let entry = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|entry| revision_of(entry) != revision)
+            .find(|entry| {
+                let environment = &entry["environment"];
+                let same_machine = environment["cpu_model"].as_str() == env.cpu_model.as_deref()
+                    && environment["profile"].as_str() == Some(env.profile.as_str());
+
+                if !same_machine {
+                    eprintln!(
+                        "Skipping the results of {}, recorded on {} with the {} profile",
+                        revision_of(entry),
+                        environment["cpu_model"].as_str().unwrap_or("an unknown CPU"),
+                        environment["profile"].as_str().unwrap_or("unknown")
+                    );
+                }
+
+                same_machine
+            })?;
+
+        let mut baseline = Baseline {
+            revision: revision_of(entry),
+            results: Vec::new(),
+            other_inputs: Vec::new(),
+        };
+
+        for benchmark in entry["benchmarks"].as_array().into_iter().flatten() {
+            if let Some(result) = BenchResult::from_json(benchmark) {
+                if benchmark["input_hash"].as_str() == env.input_hash(result.day) {
+                    baseline.results.push(result);
+                } else {
+                    baseline.other_inputs.push(result);
+                }
+            }
+        }
+
+        Some(baseline)
+    }
+
+    /// Records the results of a run, replacing the ones previously recorded on the same revision.
+    ///
+    /// Benchmarks that were not run this time are kept from the previous record.
+    pub fn record(&mut self, results: &[BenchResult], env: &Environment) {
+        let mut entry = criterion::to_json(results, env);
+        let revision = revision_of(&entry);
+
+        if let Some(pos) = self
+            .entries
+            .iter()
+            .position(|e| revision_of(e) == revision)
+        {
+            let previous = self.entries.remove(pos);
+
+            if let (Some(old), Some(new)) = (
+                previous["benchmarks"].as_array(),
+                entry["benchmarks"].as_array_mut(),
+            ) {
+                let kept: Vec<serde_json::Value> = old
+                    .iter()
+                    .filter(|o| !new.iter().any(|n| same_benchmark(o, n)))
+                    .cloned()
+                    .collect();
+                new.extend(kept);
+            }
+        }
+
+        self.entries.push(entry);
+    }
+
+    pub fn save(&self) -> Result<(), Box<dyn error::Error>> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        fs::write(&self.path, serde_json::to_string_pretty(&self.entries)?)?;
+
+        Ok(())
+    }
+}
+
+fn revision_of(entry: &serde_json::Value) -> String {
+    entry["environment"]["git_commit"]
+        .as_str()
+        .unwrap_or("unknown")
+        .to_string()
+}
+
+fn same_benchmark(a: &serde_json::Value, b: &serde_json::Value) -> bool {
+    a["day"] == b["day"] && a["part"] == b["part"] && a["implementation"] == b["implementation"]
+}
+
+/// Prints the change of each benchmark against the baseline, and returns how many regressed
+/// by more than `threshold` percents.
+///
+/// A change is significant when the confidence intervals of the medians do not overlap.
+pub fn compare(current: &[BenchResult], baseline: &Baseline, threshold: f64) -> usize {
+    let mut regressions = 0;
+
+    for result in current {
+        let display = result.display();
+
+        let base = match baseline.results.iter().find(|b| b.is_same_benchmark(result)) {
+            Some(base) => base,
+            None if baseline.other_inputs.iter().any(|b| b.is_same_benchmark(result)) => {
+                println!(
+                    "{} : {} (input changed, not compared)",
+                    display,
+                    format_time(result.median.point)
+                );
+                continue;
+            }
+            None => {
+                println!("{} : {} (new)", display, format_time(result.median.point));
+                continue;
+            }
+        };
+
+        let delta = (result.median.point - base.median.point) / base.median.point * 100.0;
+        let significant = result.median.lower_bound > base.median.upper_bound
+            || result.median.upper_bound < base.median.lower_bound;
+
+        let verdict = if !significant {
+            "no significant change"
+        } else if delta > threshold {
+            regressions += 1;
+            "REGRESSION"
+        } else if delta > 0.0 {
+            "slower"
+        } else {
+            "faster"
+        };
+
+        println!(
+            "{} : {} -> {} ({:+.2}%, {})",
+            display,
+            format_time(base.median.point),
+            format_time(result.median.point),
+            delta,
+            verdict
+        );
+    }
+
+    regressions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::criterion::Estimate;
+    use aoc_runner_internal::Day;
+
+    fn env(commit: &str, cpu: &str, input_hash: &str) -> Environment {
+        Environment {
+            git_commit: Some(commit.to_string()),
+            rustc_version: None,
+            cpu_model: Some(cpu.to_string()),
+            profile: "bench".to_string(),
+            input_hashes: vec![(Day(1), input_hash.to_string())],
+        }
+    }
+
+    fn result(median: f64) -> BenchResult {
+        let estimate = || Estimate {
+            point: median,
+            lower_bound: median * 0.99,
+            upper_bound: median * 1.01,
+        };
+
+        BenchResult {
+            day: 1,
+            part: "1".to_string(),
+            name: "(default)".to_string(),
+            median: estimate(),
+            mean: estimate(),
+            std_dev: estimate(),
+        }
+    }
+
+    fn history() -> History {
+        History {
+            path: Utf8PathBuf::from("bench-history.json"),
+            entries: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn compares_the_same_input() {
+        let mut history = history();
+        history.record(&[result(100.0)], &env("a", "cpu", "input"));
+
+        let baseline = history.baseline(&env("b", "cpu", "input")).unwrap();
+        assert_eq!(baseline.revision, "a");
+        assert_eq!(compare(&[result(200.0)], &baseline, 5.0), 1);
+    }
+
+    #[test]
+    fn does_not_compare_other_inputs() {
+        let mut history = history();
+        history.record(&[result(100.0)], &env("a", "cpu", "input"));
+
+        let baseline = history.baseline(&env("b", "cpu", "other input")).unwrap();
+        assert!(baseline.results.is_empty());
+        assert_eq!(baseline.other_inputs.len(), 1);
+        assert_eq!(compare(&[result(200.0)], &baseline, 5.0), 0);
+    }
+
+    #[test]
+    fn skips_other_machines() {
+        let mut history = history();
+        history.record(&[result(100.0)], &env("a", "cpu", "input"));
+        history.record(&[result(50.0)], &env("b", "other cpu", "input"));
+
+        let baseline = history.baseline(&env("c", "cpu", "input")).unwrap();
+        assert_eq!(baseline.revision, "a");
+        assert!(history.baseline(&env("c", "third cpu", "input")).is_none());
+    }
+}
@@ -1,6 +1,11 @@
+mod answers;
 mod app;
+mod cachegrind;
 mod credentials;
+mod criterion;
 mod date;
+mod environment;
+mod history;
 mod project;
 mod template;
 
@@ -30,14 +35,95 @@ fn main() {
         .arg(
             Arg::with_name("input")
                 .short("i")
-                .help("Use an alternate input file.")
-                .takes_value(true),
+                .help("Use an alternate input file, or `-` for stdin. Can be repeated.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
+        )
+        .arg(
+            Arg::with_name("param")
+                .long("param")
+                .help("Override a param of the solutions, as NAME=VALUE. Can be repeated.")
+                .takes_value(true)
+                .multiple(true)
+                .number_of_values(1),
         )
         .arg(
             Arg::with_name("profile")
                 .short("x")
                 .help("Add debug info for profiling tools."),
         )
+        .arg(
+            Arg::with_name("check-agree")
+                .long("check-agree")
+                .help("Check that every named implementation agrees with the default one."),
+        )
+        .arg(
+            Arg::with_name("repeat")
+                .long("repeat")
+                .short("r")
+                .help("Time each solution over this many runs, and print statistics.")
+                .takes_value(true),
+        )
+        .arg(
+            Arg::with_name("warm-up")
+                .long("warm-up")
+                .help("Number of untimed runs before timing, with --repeat. Defaults to 10.")
+                .takes_value(true)
+                .requires("repeat"),
+        )
+        .subcommand(
+            SubCommand::with_name("quick-bench")
+                .about("Time your solutions over many runs, without Criterion")
+                .arg(
+                    Arg::with_name("day")
+                        .short("d")
+                        .help("Specifies the day. Defaults to last implemented.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("part")
+                        .short("p")
+                        .help("Specifies the part. Defaults to both parts.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .short("i")
+                        .help("Use an alternate input file, or `-` for stdin. Can be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("repeat")
+                        .long("repeat")
+                        .short("r")
+                        .help("Number of timed runs.")
+                        .takes_value(true)
+                        .default_value("100"),
+                )
+                .arg(
+                    Arg::with_name("warm-up")
+                        .long("warm-up")
+                        .help("Number of untimed runs before timing.")
+                        .takes_value(true)
+                        .default_value(app::DEFAULT_WARM_UP),
+                )
+                .arg(
+                    Arg::with_name("param")
+                        .long("param")
+                        .help("Override a param of the solutions, as NAME=VALUE. Can be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("profile")
+                        .short("x")
+                        .help("Add debug info for profiling tools."),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("bench")
                 .about("Benchmark your solutions")
@@ -56,7 +142,7 @@ fn main() {
                 .arg(
                     Arg::with_name("input")
                         .short("i")
-                        .help("Use an alternate input file.")
+                        .help("Use an alternate input file, or `-` for stdin.")
                         .takes_value(true),
                 )
                 .arg(
@@ -69,12 +155,187 @@ fn main() {
                         .short("g")
                         .help("Also benchmark generator functions."),
                 )
+                .arg(
+                    Arg::with_name("cachegrind")
+                        .long("cachegrind")
+                        .help("Count instructions & cache misses under Cachegrind, instead of timing with Criterion.")
+                        .conflicts_with_all(&["open", "generator", "end-to-end", "against", "history"]),
+                )
+                .arg(
+                    Arg::with_name("end-to-end")
+                        .short("e")
+                        .long("end-to-end")
+                        .help("Time the generator and the solver together, instead of the solver alone."),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("Benchmark every implemented day.")
+                        .conflicts_with_all(&["day", "input"]),
+                )
+                .arg(
+                    Arg::with_name("compare")
+                        .long("compare")
+                        .help("Compare with the results of the previous revision (the previous run with --cachegrind), failing on regressions."),
+                )
+                .arg(
+                    Arg::with_name("threshold")
+                        .long("threshold")
+                        .help("Slowdown (in percents) from which a change is a regression. Defaults to 5.")
+                        .takes_value(true)
+                        .requires("compare"),
+                )
+                .arg(
+                    Arg::with_name("against")
+                        .long("against")
+                        .help("Also benchmark another git revision on the same input, and compare both.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("history")
+                        .long("history")
+                        .help("History file to record results in. Defaults to target/aoc/{crate}/bench-history.json.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("param")
+                        .long("param")
+                        .help("Override a param of the solutions, as NAME=VALUE. Can be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
                 .arg(
                     Arg::with_name("profile")
                         .short("x")
                         .help("Add debug info for profiling tools."),
                 ),
         )
+        .subcommand(
+            SubCommand::with_name("profile")
+                .about("Build a binary running a single solution over and over, for profilers")
+                .arg(
+                    Arg::with_name("day")
+                        .short("d")
+                        .help("Specifies the day. Defaults to last implemented.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("part")
+                        .short("p")
+                        .help("Specifies the part. Defaults to the last implemented.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("name")
+                        .short("n")
+                        .help("Specifies the name of the implementation. Defaults to the default one.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .short("i")
+                        .help("Use an alternate input file, or `-` for stdin.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("param")
+                        .long("param")
+                        .help("Override a param of the solutions, as NAME=VALUE. Can be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("iterations")
+                        .long("iterations")
+                        .help("Number of times to run the solution.")
+                        .takes_value(true)
+                        .conflicts_with("seconds"),
+                )
+                .arg(
+                    Arg::with_name("seconds")
+                        .long("seconds")
+                        .help("Run the solution for this many seconds. Defaults to 10.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("only")
+                        .long("only")
+                        .help("Only run the generator, or only the solver. Defaults to both.")
+                        .takes_value(true)
+                        .possible_values(&["generator", "solver"]),
+                )
+                .arg(
+                    Arg::with_name("tool")
+                        .long("tool")
+                        .help("Run the binary under a profiler. Otherwise, its path is printed.")
+                        .takes_value(true)
+                        .possible_values(&["perf", "callgrind"]),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("verify")
+                .about("Check your solutions against the known answers in answers/{year}.toml")
+                .arg(
+                    Arg::with_name("day")
+                        .short("d")
+                        .help("Specifies the day. Defaults to last implemented.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("part")
+                        .short("p")
+                        .help("Specifies the part. Defaults to both parts.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("Verify every implemented day.")
+                        .conflicts_with("day"),
+                )
+                .arg(
+                    Arg::with_name("fetch")
+                        .long("fetch")
+                        .help("Fetch the missing answers from the already solved puzzles."),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("diff")
+                .about("Compare the answers of your solutions with those of another git revision")
+                .arg(
+                    Arg::with_name("rev")
+                        .help("The revision to compare to (a branch, a tag, a commit...)")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("day")
+                        .short("d")
+                        .help("Specifies the day. Defaults to last implemented.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("part")
+                        .short("p")
+                        .help("Specifies the part. Defaults to both parts.")
+                        .takes_value(true),
+                )
+                .arg(
+                    Arg::with_name("input")
+                        .short("i")
+                        .help("Use an alternate input file, or `-` for stdin. Can be repeated.")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1),
+                )
+                .arg(
+                    Arg::with_name("all")
+                        .long("all")
+                        .help("Compare every implemented day.")
+                        .conflicts_with_all(&["day", "input"]),
+                ),
+        )
         .subcommand(
             SubCommand::with_name("credentials")
                 .about("Manage your AOC credentials information")
@@ -115,7 +376,37 @@ fn main() {
                 std::process::exit(-1);
             }
         }
+        ("quick-bench", Some(m)) => {
+            if let Err(e) = app.execute_default(m) {
+                eprintln!("An error occurs : {}", e);
+                std::process::exit(-1);
+            }
+        }
+        ("profile", Some(m)) => {
+            if let Err(e) = app.execute_profile(m) {
+                eprintln!("An error occurs : {}", e);
+                std::process::exit(-1);
+            }
+        }
+        ("verify", Some(m)) => {
+            if let Err(e) = app.execute_verify(m) {
+                eprintln!("An error occurs : {}", e);
+                std::process::exit(-1);
+            }
+        }
+        ("diff", Some(m)) => {
+            if let Err(e) = app.execute_diff(m) {
+                eprintln!("An error occurs : {}", e);
+                std::process::exit(-1);
+            }
+        }
         (c, Some(_)) => panic!("Unknown command `{}`", c),
+        _ if matches.is_present("check-agree") => {
+            if let Err(e) = app.execute_check_agree(&matches) {
+                eprintln!("An error occurs : {}", e);
+                std::process::exit(-1);
+            }
+        }
         _ => {
             if let Err(e) = app.execute_default(&matches) {
                 eprintln!("An error occurs : {}", e);
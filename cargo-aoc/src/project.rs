@@ -1,5 +1,7 @@
-use aoc_runner_internal::DayParts;
-use camino::Utf8PathBuf;
+use crate::app::write_autobuild;
+use crate::template::{ListMain, Templates};
+use aoc_runner_internal::{DayPart, DayParts, DayPartsBuilder, GeneratorFn, Part};
+use camino::{Utf8Path, Utf8PathBuf};
 use cargo_metadata::{MetadataCommand, Package};
 use std::error;
 use std::process;
@@ -50,20 +52,38 @@ impl ProjectManager {
         })
     }
 
+    /// Lists the solutions of the crate, with a binary linking it, which prints those it collected
     pub fn build_project(&self) -> Result<DayParts, Box<dyn error::Error>> {
-        let args = vec!["check", "--color=always"];
+        let templates = Templates::load(&self.crate_dir)?;
+        let autolist_dir = self
+            .root_target_dir
+            .join("aoc")
+            .join(&self.slug)
+            .join("aoc-autolist");
 
-        let status = process::Command::new("cargo").args(&args).spawn()?.wait()?;
+        let main_content = templates.render(
+            "src/list.rs.tpl",
+            &ListMain {
+                crate_slug: &self.slug,
+            },
+        )?;
+        write_autobuild(&templates, self, &autolist_dir, false, &main_content)?;
 
-        if !status.success() {
+        let output = process::Command::new("cargo")
+            .args(["run", "--color=always"])
+            .current_dir(&autolist_dir)
+            .stderr(process::Stdio::inherit())
+            .output()?;
+
+        if !output.status.success() {
             return Err(format!(
                 "cargo build failed with code {}",
-                status.code().unwrap_or(-1)
+                output.status.code().unwrap_or(-1)
             )
             .into());
         }
 
-        DayParts::load(self.slug.clone(), Some(self.root_target_dir.clone().into()))
+        parse_solutions(&String::from_utf8(output.stdout)?)
     }
 
     pub fn input_file_for(&self, year: u32, day: aoc_runner_internal::Day) -> Utf8PathBuf {
@@ -72,4 +92,130 @@ impl ProjectManager {
             .join(year.to_string())
             .join(format!("day{}.txt", day.0))
     }
+
+    /// Gets the same crate, as checked out in `worktree`
+    ///
+    /// The worktree gets its own target directory, so that building it does not touch ours.
+    pub fn in_worktree(&self, worktree: &Worktree) -> Result<ProjectManager, Box<dyn error::Error>> {
+        let prefix = git(&self.crate_dir, &["rev-parse", "--show-prefix"])?;
+
+        Ok(ProjectManager {
+            name: self.name.clone(),
+            slug: self.slug.clone(),
+            root_target_dir: worktree.path.join("target"),
+            crate_dir: worktree.path.join(prefix),
+        })
+    }
+}
+
+/// A temporary checkout of another revision of the repository, removed when dropped
+pub struct Worktree {
+    pub path: Utf8PathBuf,
+    /// Full hash of the checked out commit
+    pub commit: String,
+    repo_dir: Utf8PathBuf,
+}
+
+impl Worktree {
+    /// Checks out `rev` of the repository holding `repo_dir` in a temporary directory
+    pub fn checkout(repo_dir: &Utf8Path, rev: &str) -> Result<Worktree, Box<dyn error::Error>> {
+        let commit = git(repo_dir, &["rev-parse", "--verify", &format!("{}^{{commit}}", rev)])
+            .map_err(|_| format!("Unknown revision `{}`", rev))?;
+
+        let tmp_dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+            .map_err(|e| format!("Non unicode path: {}", e.display()))?;
+        let path = tmp_dir.join(format!("cargo-aoc-{}", &commit[..12]));
+
+        // Leftover of an interrupted run
+        if path.exists() {
+            let _ = git(repo_dir, &["worktree", "remove", "--force", path.as_str()]);
+            let _ = std::fs::remove_dir_all(&path);
+            let _ = git(repo_dir, &["worktree", "prune"]);
+        }
+
+        git(repo_dir, &["worktree", "add", "--detach", path.as_str(), &commit])?;
+
+        Ok(Worktree {
+            path,
+            commit,
+            repo_dir: repo_dir.to_path_buf(),
+        })
+    }
+}
+
+impl Drop for Worktree {
+    fn drop(&mut self) {
+        if let Err(e) = git(
+            &self.repo_dir,
+            &["worktree", "remove", "--force", self.path.as_str()],
+        ) {
+            eprintln!("Failed to remove worktree {} : {}", self.path, e);
+        }
+    }
+}
+
+/// Reads the solutions printed by `src/list.rs.tpl`
+fn parse_solutions(list: &str) -> Result<DayParts, Box<dyn error::Error>> {
+    let mut lines = list.lines();
+    let year = lines
+        .next()
+        .ok_or("No year found in the list of solutions")?
+        .trim()
+        .parse()?;
+
+    let mut parts = Vec::new();
+    let mut generators = Vec::new();
+    let mut both = Vec::new();
+
+    for line in lines {
+        let fields: Vec<&str> = line.split('\t').collect();
+        if fields.len() != 6 {
+            return Err(format!("Invalid solution in the list of solutions: {}", line).into());
+        }
+
+        let dp = DayPart {
+            day: fields[0].parse()?,
+            part: fields[1].parse()?,
+            name: Some(fields[2]).filter(|n| !n.is_empty()).map(str::to_string),
+        };
+
+        if !fields[3].is_empty() {
+            let generator = GeneratorFn {
+                name: fields[3].to_string(),
+                path: fields[4].to_string(),
+            };
+            generators.push((dp.clone(), generator));
+        }
+        if fields[5] == "true" && dp.part == Part(1) {
+            both.push(dp.clone());
+        }
+
+        parts.push(dp);
+    }
+
+    Ok(parts
+        .into_iter()
+        .collect::<DayPartsBuilder>()
+        .with_year(year)
+        .with_generators(generators)
+        .with_both(both))
+}
+
+/// Runs a git command, returning its trimmed output
+fn git(dir: &Utf8Path, args: &[&str]) -> Result<String, Box<dyn error::Error>> {
+    let output = process::Command::new("git")
+        .args(args)
+        .current_dir(dir)
+        .output()?;
+
+    if !output.status.success() {
+        return Err(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )
+        .into());
+    }
+
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
 }
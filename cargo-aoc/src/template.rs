@@ -1,3 +1,6 @@
+use camino::Utf8Path;
+use serde_derive::Serialize;
+use std::error;
 use tera::Tera;
 
 macro_rules! get_builtin(($path: literal) => {
@@ -9,17 +12,237 @@ const STATIC_TEMPLATES: &[(&str, &str)] = &[
     get_builtin!("Cargo-run.toml.tpl"),
     get_builtin!("input.rs.tpl"),
     get_builtin!("benches/aoc_benchmark.rs.tpl"),
+    get_builtin!("benches/e2e_impl.rs.tpl"),
     get_builtin!("benches/gen_impl.rs.tpl"),
     get_builtin!("benches/gen.rs.tpl"),
     get_builtin!("benches/impl.rs.tpl"),
     get_builtin!("benches/part.rs.tpl"),
+    get_builtin!("src/answer.rs.tpl"),
+    get_builtin!("src/answers.rs.tpl"),
+    get_builtin!("src/cachegrind.rs.tpl"),
+    get_builtin!("src/cachegrind_case.rs.tpl"),
+    get_builtin!("src/list.rs.tpl"),
     get_builtin!("src/main.rs.tpl"),
+    get_builtin!("src/profile.rs.tpl"),
+    get_builtin!("src/quick_bench.rs.tpl"),
     get_builtin!("src/runner.rs.tpl"),
+    get_builtin!("src/stats.rs.tpl"),
 ];
 
-pub fn get_tera() -> Tera {
-    let mut tera = Tera::default();
-    tera.add_raw_templates(STATIC_TEMPLATES.iter().map(|t| t.clone()))
-        .expect("Invalid built in templates?");
-    tera
+/// Directory of a crate holding templates overriding the built-in ones, with the same paths
+const OVERRIDES_DIR: &str = "aoc-templates";
+
+/// The templates of the generated crates
+pub struct Templates {
+    tera: Tera,
+}
+
+impl Templates {
+    /// Loads the built-in templates, overridden by those in the `aoc-templates/` directory of the crate
+    pub fn load(crate_dir: &Utf8Path) -> Result<Templates, Box<dyn error::Error>> {
+        let mut tera = Tera::default();
+        tera.add_raw_templates(STATIC_TEMPLATES.iter().copied())
+            .expect("Invalid built in templates?");
+
+        let overrides_dir = crate_dir.join(OVERRIDES_DIR);
+        if overrides_dir.is_dir() {
+            let mut files = Vec::new();
+            let mut dirs = vec![overrides_dir.clone()];
+
+            while let Some(dir) = dirs.pop() {
+                for entry in dir.read_dir_utf8()? {
+                    let entry = entry?;
+                    if entry.file_type()?.is_dir() {
+                        dirs.push(entry.path().to_path_buf());
+                    } else {
+                        let name = entry
+                            .path()
+                            .strip_prefix(&overrides_dir)?
+                            .components()
+                            .map(|c| c.as_str())
+                            .collect::<Vec<_>>()
+                            .join("/");
+                        files.push((entry.path().to_path_buf(), Some(name)));
+                    }
+                }
+            }
+
+            tera.add_template_files(files)
+                .map_err(|e| format!("Invalid template in {} : {}", overrides_dir, describe(&e)))?;
+        }
+
+        Ok(Templates { tera })
+    }
+
+    pub fn render<C: serde::Serialize>(&self, name: &str, context: &C) -> Result<String, Box<dyn error::Error>> {
+        let context = tera::Context::from_serialize(context)?;
+
+        self.tera
+            .render(name, &context)
+            .map_err(|e| describe(&e).into())
+    }
+}
+
+/// Tera only tells what went wrong in the sources of its errors
+fn describe(error: &tera::Error) -> String {
+    let mut description = error.to_string();
+    let mut source = error::Error::source(error);
+
+    while let Some(e) = source {
+        description += &format!(": {}", e);
+        source = e.source();
+    }
+
+    description
+}
+
+/// `Cargo-bench.toml.tpl` & `Cargo-run.toml.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Manifest<'a> {
+    pub crate_name: &'a str,
+    /// Already escaped
+    pub crate_path: String,
+    /// Adds debug info for profiling tools
+    pub debug_info: bool,
+}
+
+/// `input.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Inputs {
+    pub day: u8,
+    pub inputs: Vec<Input>,
+}
+
+#[derive(Serialize)]
+pub struct Input {
+    /// Already escaped
+    pub name: String,
+    /// Already escaped
+    pub path: String,
+}
+
+/// A single solution, for `src/answer.rs.tpl`, `src/runner.rs.tpl` & `src/cachegrind_case.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct Solution<'a> {
+    pub day: u8,
+    pub part: u8,
+    /// Empty for the default implementation
+    pub name: &'a str,
+    /// Name of the method of `Factory` building the runner
+    pub runner_name: String,
+    pub runner_display: String,
+    /// Path of the generator function, keying its output shared with the other solutions using it
+    pub generator: Option<&'a str>,
+    /// Display of part 2, when the solution solves both parts in a single call, to report them together
+    pub both: Option<String>,
+}
+
+/// `src/quick_bench.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct QuickBench<'a> {
+    #[serde(flatten)]
+    pub solution: &'a Solution<'a>,
+    pub repeat: u32,
+    pub warm_up: u32,
+}
+
+/// `src/main.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct RunMain<'a> {
+    pub crate_slug: &'a str,
+    pub year: u32,
+    pub day: u8,
+    pub input: String,
+    pub body: String,
+    /// Includes `src/stats.rs.tpl`
+    pub stats: bool,
+}
+
+/// `src/answers.rs.tpl` & `src/cachegrind.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct SolutionsMain<'a> {
+    pub crate_slug: &'a str,
+    pub inputs: String,
+    pub body: String,
+}
+
+/// `src/list.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct ListMain<'a> {
+    pub crate_slug: &'a str,
+}
+
+/// `src/profile.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct ProfileMain<'a> {
+    #[serde(flatten)]
+    pub solution: &'a Solution<'a>,
+    pub crate_slug: &'a str,
+    pub input: String,
+    /// `generator` or `solver`, or nothing to run both
+    pub only: Option<&'a str>,
+    /// Number of iterations, otherwise the solution is run for `seconds`
+    pub iterations: Option<u64>,
+    pub seconds: f64,
+}
+
+/// `benches/aoc_benchmark.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct BenchMain<'a> {
+    pub crate_slug: &'a str,
+    pub inputs: String,
+    pub parts: String,
+    pub gens: String,
+    /// Whether generators are benchmarked
+    pub generators: bool,
+}
+
+/// `benches/part.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct BenchPart<'a> {
+    pub part_name: &'a str,
+    pub day: u8,
+    pub part: u8,
+    /// Whether the implementations time the generator & the solver together
+    pub total: bool,
+    pub impls: String,
+}
+
+/// `benches/impl.rs.tpl` & `benches/e2e_impl.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct BenchImpl<'a> {
+    pub part_name: &'a str,
+    pub day: u8,
+    pub name: &'a str,
+    pub runner_name: String,
+}
+
+/// `benches/gen.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct BenchGen<'a> {
+    pub gen_name: &'a str,
+    pub day: u8,
+    pub impls: String,
+}
+
+/// `benches/gen_impl.rs.tpl`
+#[derive(Serialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct BenchGenImpl<'a> {
+    pub gen_name: &'a str,
+    pub day: u8,
+    pub name: &'a str,
+    pub runner_name: String,
 }
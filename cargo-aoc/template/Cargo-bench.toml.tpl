@@ -6,13 +6,14 @@ version = "0.3.0"
 authors = ["Grégory Obanos <gregory.obanos@gmail.com>"]
 
 [dependencies]
-{CRATE_NAME} = { path = "{CRATE_PATH}" }
-aoc-runner = "0.3"
+{{CRATE_NAME}} = { path = "{{CRATE_PATH}}" }
+aoc-runner = "0.4"
 
 [dev-dependencies]
-criterion = "0.2.5"
+criterion = "0.2.10"
 
-{PROFILE}
+{% if DEBUG_INFO %}[profile.release]
+debug = true{% endif %}
 
 [[bench]]
 name = "aoc_benchmark"
@@ -6,7 +6,8 @@ version = "0.3.0"
 authors = ["Grégory Obanos <gregory.obanos@gmail.com>"]
 
 [dependencies]
-{CRATE_NAME} = { path = "{CRATE_PATH}" }
-aoc-runner = "0.3"
+{{CRATE_NAME}} = { path = "{{CRATE_PATH}}" }
+aoc-runner = "0.4"
 
-{PROFILE}
\ No newline at end of file
+{% if DEBUG_INFO %}[profile.release]
+debug = true{% endif %}
\ No newline at end of file
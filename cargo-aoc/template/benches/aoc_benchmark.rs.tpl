@@ -1,10 +1,11 @@
 #[macro_use]
 extern crate criterion;
-extern crate {CRATE_SLUG};
+extern crate {{CRATE_SLUG}};
 extern crate aoc_runner;
 
-use {CRATE_SLUG}::*;
+use {{CRATE_SLUG}}::*;
 use aoc_runner::ArcStr;
+use criterion::BatchSize;
 use criterion::Criterion;
 use criterion::Fun;
 use std::fmt::Display;
@@ -15,18 +16,18 @@ fn black_box(t: &dyn Display) {
 }
 
 fn aoc_benchmark(c: &mut Criterion) {
-    {INPUTS}
+    {{INPUTS}}
 
-    {PARTS}
+    {{PARTS}}
 }
 
 #[allow(unused_variables)]
 #[allow(dead_code)]
 fn input_benchmark(c: &mut Criterion) {
-    {INPUTS}
+    {{INPUTS}}
 
-    {GENS}
+    {{GENS}}
 }
 
-criterion_group!(benches, {BENCHMARKS});
+criterion_group!(benches, aoc_benchmark{% if GENERATORS %}, input_benchmark{% endif %});
 criterion_main!(benches);
@@ -0,0 +1,10 @@
+
+    {
+        let input = inputs_day{{DAY}}[0].1.clone();
+        let fun = Fun::new("{{NAME}}", move |b, _| b.iter(|| {
+            let runner = Factory::{{RUNNER_NAME}}(input.clone())
+                .expect("failed to generate input for {{NAME}}");
+            black_box(&*runner.try_run().expect("failed to run {{NAME}}"))
+        }));
+        {{PART_NAME}}.push(fun);
+    }
\ No newline at end of file
@@ -1,6 +1,6 @@
 
-    let mut {GEN_NAME} = Vec::new();
+    let mut {{GEN_NAME}} = Vec::new();
 
-    {IMPLS}
+    {{IMPLS}}
 
-    c.bench_functions("Generator Day{DAY}", {GEN_NAME}, ());
\ No newline at end of file
+    c.bench_functions("Generator Day{{DAY}}", {{GEN_NAME}}, ());
\ No newline at end of file
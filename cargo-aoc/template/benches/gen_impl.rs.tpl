@@ -1,6 +1,6 @@
 
     {
-        let input = input_day{DAY}.clone();
-        let fun = Fun::new("{NAME}", move |b, _| b.iter(|| Factory::{RUNNER_NAME}(input.clone()).unwrap()));
-        {GEN_NAME}.push(fun);
+        let input = inputs_day{{DAY}}[0].1.clone();
+        let fun = Fun::new("{{NAME}}", move |b, _| b.iter(|| Factory::{{RUNNER_NAME}}(input.clone()).unwrap()));
+        {{GEN_NAME}}.push(fun);
     }
\ No newline at end of file
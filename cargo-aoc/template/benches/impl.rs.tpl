@@ -1,7 +1,11 @@
 
     {
-        let runner = Factory::{RUNNER_NAME}(input_day{DAY}.clone())
-            .expect("failed to generate input for {NAME}");
-        let fun = Fun::new("{NAME}", move |b, _| b.iter(|| runner.bench(black_box)));
-        {PART_NAME}.push(fun);
+        let runner = Factory::{{RUNNER_NAME}}(inputs_day{{DAY}}[0].1.clone())
+            .expect("failed to generate input for {{NAME}}");
+        let fun = Fun::new("{{NAME}}", move |b, _| b.iter_batched(
+            || runner.prepare(),
+            |input| runner.bench_prepared(input, black_box),
+            BatchSize::SmallInput,
+        ));
+        {{PART_NAME}}.push(fun);
     }
\ No newline at end of file
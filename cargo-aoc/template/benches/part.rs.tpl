@@ -1,6 +1,6 @@
 
-    let mut {PART_NAME} = Vec::new();
+    let mut {{PART_NAME}} = Vec::new();
 
-    {IMPLS}
+    {{IMPLS}}
 
-    c.bench_functions("Day{DAY} - Part{PART}", {PART_NAME}, ());
\ No newline at end of file
+    c.bench_functions("Day{{DAY}} - Part{{PART}}{% if TOTAL %} (total){% endif %}", {{PART_NAME}}, ());
\ No newline at end of file
@@ -1,2 +1,4 @@
 
-    let input_day{{DAY}} = ArcStr::from(include_str!("{{PATH}}"));
+    let inputs_day{{DAY}} = [{% for input in INPUTS %}
+        ("{{input.name}}", ArcStr::from(include_str!("{{input.path}}"))),{% endfor %}
+    ];
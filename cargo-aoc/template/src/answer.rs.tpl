@@ -0,0 +1,7 @@
+
+    for (input_name, input) in inputs_day{{DAY}}.iter() {
+        match Factory::{{RUNNER_NAME}}(input.clone()).and_then(|runner| runner.try_run()) {
+            Ok(result) => println!("ANSWER\t{}\t{{DAY}}\t{{PART}}\t{{NAME}}\t{:?}", input_name, result.to_string()),
+            Err(e) => eprintln!("{{RUNNER_DISPLAY}} [{}] : FAILED :\n{:#?}\n", input_name, e),
+        }
+    }
\ No newline at end of file
@@ -0,0 +1,11 @@
+extern crate {{CRATE_SLUG}};
+extern crate aoc_runner;
+
+use {{CRATE_SLUG}}::*;
+use aoc_runner::ArcStr;
+
+fn main() {
+    {{INPUTS}}
+
+    {{BODY}}
+}
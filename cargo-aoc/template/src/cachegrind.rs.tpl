@@ -0,0 +1,21 @@
+extern crate {{CRATE_SLUG}};
+extern crate aoc_runner;
+
+use {{CRATE_SLUG}}::*;
+use aoc_runner::ArcStr;
+use std::hint::black_box;
+
+// Usage: aoc-autobuild <solution> calibrate|run
+//
+// `calibrate` only generates the input, so that its counts can be removed from those of `run`.
+fn main() {
+    let args: Vec<String> = std::env::args().collect();
+    let solve = args[2] == "run";
+
+    {{INPUTS}}
+
+    match args[1].as_str() {
+        {{BODY}}
+        solution => panic!("unknown solution {}", solution),
+    }
+}
@@ -0,0 +1,8 @@
+
+        "{{RUNNER_NAME}}" => {
+            let runner = Factory::{{RUNNER_NAME}}(inputs_day{{DAY}}[0].1.clone())
+                .expect("failed to generate input for {{RUNNER_DISPLAY}}");
+            if solve {
+                black_box(runner.try_run().expect("failed to run {{RUNNER_DISPLAY}}"));
+            }
+        }
\ No newline at end of file
@@ -0,0 +1,20 @@
+extern crate {{CRATE_SLUG}};
+extern crate aoc_runner;
+
+// One solution per line : day, part, name, generator name & path, and whether it solves both parts
+fn main() {
+    let year = {{CRATE_SLUG}}::YEAR;
+    println!("{}", year);
+
+    for solution in aoc_runner::registered().into_iter().filter(|s| s.year == year) {
+        println!(
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            solution.day,
+            solution.part,
+            solution.name.unwrap_or(""),
+            solution.generator.unwrap_or(""),
+            solution.generator_path.unwrap_or(""),
+            solution.both
+        );
+    }
+}
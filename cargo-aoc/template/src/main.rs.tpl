@@ -1,14 +1,63 @@
-extern crate {CRATE_SLUG};
+extern crate {{CRATE_SLUG}};
 extern crate aoc_runner;
 
-use {CRATE_SLUG}::*;
+use {{CRATE_SLUG}}::*;
 use std::time::Instant;
 use aoc_runner::ArcStr;
 
 fn main() {
-    println!("AOC {YEAR}");
+    println!("AOC {{YEAR}}");
 
-    {INPUT}
+    {{INPUT}}
 
-    {BODY}
-}
\ No newline at end of file
+    let mut results: Vec<(&str, Vec<String>)> = Vec::new();
+
+    // Outputs of the generators, by generator & input, shared by the solutions using them
+    #[allow(unused_mut, unused_variables)]
+    let mut shared_inputs: std::collections::HashMap<(&str, usize), std::rc::Rc<dyn std::any::Any>> =
+        std::collections::HashMap::new();
+    // States given by part 1 when it ran, by input, for the part 2 solutions taking them
+    #[allow(unused_mut, unused_variables)]
+    let mut part1_states: std::collections::HashMap<usize, std::rc::Rc<dyn std::any::Any>> =
+        std::collections::HashMap::new();
+
+    {{BODY}}
+
+    if inputs_day{{DAY}}.len() > 1 {
+        print_results(&inputs_day{{DAY}}, &results);
+    }
+}
+
+fn print_results(inputs: &[(&str, ArcStr)], results: &[(&str, Vec<String>)]) {
+    let mut header = vec![""];
+    header.extend(inputs.iter().map(|(name, _)| *name));
+
+    let rows: Vec<Vec<&str>> = results
+        .iter()
+        .map(|(display, answers)| {
+            let mut row = vec![*display];
+            row.extend(answers.iter().map(|a| a.as_str()));
+            row
+        })
+        .collect();
+
+    let widths: Vec<usize> = (0..header.len())
+        .map(|i| {
+            rows.iter()
+                .map(|row| row[i].len())
+                .chain(Some(header[i].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    for row in Some(&header).into_iter().chain(rows.iter()) {
+        let line: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join(" | ").trim_end());
+    }
+}
+{% if STATS %}{% include "src/stats.rs.tpl" %}{% endif %}
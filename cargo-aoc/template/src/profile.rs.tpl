@@ -0,0 +1,40 @@
+extern crate {{CRATE_SLUG}};
+extern crate aoc_runner;
+
+use {{CRATE_SLUG}}::*;
+use aoc_runner::ArcStr;
+use std::hint::black_box;
+use std::time::Instant;
+
+fn main() {
+    {{INPUT}}
+
+    let input = inputs_day{{DAY}}[0].1.clone();
+{%- if ONLY == "solver" %}
+    let runner = Factory::{{RUNNER_NAME}}(input.clone())
+        .expect("failed to generate input for {{RUNNER_DISPLAY}}");
+{%- endif %}
+
+    let start_time = Instant::now();
+    let mut iterations: u64 = 0;
+
+{% if ITERATIONS %}    while iterations < {{ITERATIONS}} {
+{%- else %}    while start_time.elapsed() < std::time::Duration::from_secs_f64({{SECONDS}}) {
+{%- endif %}
+{%- if ONLY == "generator" %}
+        black_box(
+            Factory::{{RUNNER_NAME}}(input.clone())
+                .expect("failed to generate input for {{RUNNER_DISPLAY}}"),
+        );
+{%- elif ONLY == "solver" %}
+        black_box(runner.try_run().expect("failed to run {{RUNNER_DISPLAY}}"));
+{%- else %}
+        let runner = Factory::{{RUNNER_NAME}}(input.clone())
+            .expect("failed to generate input for {{RUNNER_DISPLAY}}");
+        black_box(runner.try_run().expect("failed to run {{RUNNER_DISPLAY}}"));
+{%- endif %}
+        iterations += 1;
+    }
+
+    println!("{{RUNNER_DISPLAY}} : {} iterations in {:?}", iterations, start_time.elapsed());
+}
@@ -0,0 +1,65 @@
+
+    {
+        let mut answers = Vec::new();
+
+        for (input_name, input) in inputs_day{{DAY}}.iter() {
+            let display = if inputs_day{{DAY}}.len() > 1 {
+                format!("{{RUNNER_DISPLAY}} [{}]", input_name)
+            } else {
+                "{{RUNNER_DISPLAY}}".to_string()
+            };
+
+            for _ in 0..{{WARM_UP}} {
+                if let Ok(runner) = Factory::{{RUNNER_NAME}}(input.clone()) {
+                    let _ = runner.try_run();
+                }
+            }
+
+            let mut gen_times = Vec::with_capacity({{REPEAT}});
+            let mut run_times = Vec::with_capacity({{REPEAT}});
+            // Any failed run fails the solution, even after others succeeded
+            let mut answer = None;
+
+            for _ in 0..{{REPEAT}} {
+                let start_time = Instant::now();
+
+                let runner = match Factory::{{RUNNER_NAME}}(input.clone()) {
+                    Ok(runner) => runner,
+                    Err(e) => {
+                        eprintln!("{} : FAILED while generating :\n{:#?}\n", display, e);
+                        answer = None;
+                        break;
+                    }
+                };
+
+                let inter_time = Instant::now();
+                // Solvers taking the input by value get their copy before the timer starts
+                let prepared = runner.prepare();
+                let run_time = Instant::now();
+
+                match runner.try_run_prepared(prepared) {
+                    Ok((result, _rest)) => {
+                        let final_time = Instant::now();
+                        gen_times.push(inter_time - start_time);
+                        run_times.push(final_time - run_time);
+                        answer = Some(result.to_string());
+                    },
+                    Err(e) => {
+                        eprintln!("{} : FAILED while running :\n{:#?}\n", display, e);
+                        answer = None;
+                        break;
+                    }
+                }
+            }
+
+            match answer {
+                Some(answer) => {
+                    println!("{} : {}\n\tgenerator: {},\n\trunner: {}\n", display, answer, Stats::of(&gen_times), Stats::of(&run_times));
+                    answers.push(answer);
+                },
+                None => answers.push("FAILED".to_string()),
+            }
+        }
+
+        results.push(("{{RUNNER_DISPLAY}}", answers));
+    }
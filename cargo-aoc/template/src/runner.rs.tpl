@@ -1,19 +1,106 @@
 
     {
-        let start_time = Instant::now();
-
-        match Factory::{RUNNER_NAME}(input_day{DAY}.clone()) {
-            Ok(runner) => {
-                let inter_time = Instant::now();
-
-                match runner.try_run() {
-                    Ok(result) => {
-                        let final_time = Instant::now();
-                        println!("{RUNNER_DISPLAY} : {}\n\tgenerator: {:?},\n\trunner: {:?}\n", result, (inter_time - start_time), (final_time - inter_time));
-                    },
-                    Err(e) => eprintln!("{RUNNER_DISPLAY} : FAILED while running :\n{:#?}\n", e)
+        let mut answers = Vec::new();
+{% if BOTH %}
+        // Part 2 is answered by the same call
+        let mut second_answers = Vec::new();
+{% endif %}
+        for (i, (input_name, input)) in inputs_day{{DAY}}.iter().enumerate() {
+            let display = if inputs_day{{DAY}}.len() > 1 {
+                format!("{{RUNNER_DISPLAY}} [{}]", input_name)
+            } else {
+                "{{RUNNER_DISPLAY}}".to_string()
+            };
+{% if BOTH %}
+            let second_display = if inputs_day{{DAY}}.len() > 1 {
+                format!("{{BOTH}} [{}]", input_name)
+            } else {
+                "{{BOTH}}".to_string()
+            };
+{% endif %}
+            let start_time = Instant::now();
+{% if GENERATOR %}
+            // The output of a generator already run for another solution is reused
+            let shared = shared_inputs.get(&("{{GENERATOR}}", i)).cloned();
+{% else %}
+            let shared: Option<std::rc::Rc<dyn std::any::Any>> = None;
+{% endif %}
+{% if PART == 2 %}
+            // The state part 1 gave when it ran is reused, instead of running part 1 again
+            let runner = match (part1_states.get(&i), &shared) {
+                (Some(state), _) => Factory::stated_{{RUNNER_NAME}}(input.clone(), shared.clone(), state.clone()),
+                (None, Some(shared)) => Factory::shared_{{RUNNER_NAME}}(shared.clone()),
+                (None, None) => Factory::{{RUNNER_NAME}}(input.clone()),
+            };
+{% else %}
+            let runner = match &shared {
+                Some(shared) => Factory::shared_{{RUNNER_NAME}}(shared.clone()),
+                None => Factory::{{RUNNER_NAME}}(input.clone()),
+            };
+{% endif %}
+            let answer = match runner {
+                Ok(runner) => {
+                    let inter_time = Instant::now();
+{% if GENERATOR %}
+                    if let Some(input) = runner.shared_input() {
+                        shared_inputs.entry(("{{GENERATOR}}", i)).or_insert(input);
+                    }
+{% endif %}
+{% if BOTH %}
+                    match runner.try_run_both() {
+                        Ok((result, second)) => {
+                            let final_time = Instant::now();
+                            let generator = if shared.is_some() {
+                                "shared".to_string()
+                            } else {
+                                format!("{:?}", inter_time - start_time)
+                            };
+                            println!("{} : {}\n{} : {}\n\tgenerator: {},\n\trunner: {:?}\n", display, result, second_display, second, generator, (final_time - inter_time));
+                            second_answers.push(second.to_string());
+                            result.to_string()
+                        },
+                        Err(e) => {
+                            eprintln!("{} : FAILED while running :\n{:#?}\n", display, e);
+                            second_answers.push("FAILED".to_string());
+                            "FAILED".to_string()
+                        }
+                    }
+{% else %}
+                    match runner.try_run() {
+                        Ok(result) => {
+                            let final_time = Instant::now();
+                            if let Some(state) = runner.shared_state() {
+                                part1_states.insert(i, state);
+                            }
+                            let generator = if shared.is_some() {
+                                "shared".to_string()
+                            } else {
+                                format!("{:?}", inter_time - start_time)
+                            };
+                            println!("{} : {}\n\tgenerator: {},\n\trunner: {:?}\n", display, result, generator, (final_time - inter_time));
+                            result.to_string()
+                        },
+                        Err(e) => {
+                            eprintln!("{} : FAILED while running :\n{:#?}\n", display, e);
+                            "FAILED".to_string()
+                        }
+                    }
+{% endif %}
+                },
+                Err(e) => {
+                    eprintln!("{} : FAILED while generating :\n{:#?}\n", display, e);
+{% if BOTH %}
+                    second_answers.push("FAILED".to_string());
+{% endif %}
+                    "FAILED".to_string()
                 }
-            },
-            Err(e) => eprintln!("{RUNNER_DISPLAY} : FAILED while generating :\n{:#?}\n", e)
+            };
+
+            answers.push(answer);
         }
-    }
\ No newline at end of file
+
+        results.push(("{{RUNNER_DISPLAY}}", answers));
+{% if BOTH %}
+        results.push(("{{BOTH}}", second_answers));
+{% endif %}
+    }
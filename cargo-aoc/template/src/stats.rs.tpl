@@ -0,0 +1,2 @@
+
+use aoc_runner::Stats;
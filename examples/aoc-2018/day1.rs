@@ -13,6 +13,20 @@ fn part1(freqs: &[i32]) -> i32 {
     freqs.iter().sum()
 }
 
+#[aoc(day1, part1, Owned)]
+fn part1_owned(freqs: Vec<i32>) -> i32 {
+    freqs.into_iter().sum()
+}
+
+#[aoc(day1, part1, InPlace)]
+fn part1_in_place(freqs: &mut [i32]) -> i32 {
+    for i in 1..freqs.len() {
+        freqs[i] += freqs[i - 1];
+    }
+
+    freqs.last().copied().unwrap_or(0)
+}
+
 #[aoc(day1, part2)]
 fn part2(freqs: &[i32]) -> i32 {
     let mut reached = HashSet::new();
@@ -63,6 +77,30 @@ mod tests {
         assert_eq!(part1(&[-1, -2, -3]), -6);
     }
 
+    #[test]
+    fn part1_owned_example() {
+        assert_eq!(part1_owned(vec![1, -2, 3, 1]), 3);
+        assert_eq!(part1_owned(vec![-1, -2, -3]), -6);
+    }
+
+    #[test]
+    fn part1_in_place_example() {
+        assert_eq!(part1_in_place(&mut [1, -2, 3, 1]), 3);
+        assert_eq!(part1_in_place(&mut [-1, -2, -3]), -6);
+        assert_eq!(part1_in_place(&mut []), 0);
+    }
+
+    #[test]
+    fn part1_from_registry() {
+        let solution = aoc_runner::solutions()
+            .into_iter()
+            .find(|s| s.year == 2018 && s.day == 1 && s.part == 1 && s.name.is_none())
+            .expect("day 1 part 1 is not registered");
+
+        assert!(solution.has_generator);
+        assert_eq!(aoc_runner::run(&solution, "+1\n-2\n+3\n+1").unwrap().to_string(), "3");
+    }
+
     #[test]
     fn part2_example() {
         assert_eq!(part2(&[1, -2, 3, 1]), 2);
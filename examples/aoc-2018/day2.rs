@@ -1,6 +1,9 @@
 use aoc_runner_derive::aoc;
 use fnv::FnvHashMap;
 use std::collections::HashMap;
+use std::fmt::Display;
+
+type Answer<T> = Result<T, String>;
 
 #[aoc(day2, part1)]
 fn part1(input: &str) -> u32 {
@@ -72,6 +75,31 @@ fn part2(input: &str) -> String {
     unreachable!()
 }
 
+#[aoc(day2, part2, Checked)]
+fn part2_checked(input: &str) -> Answer<impl Display> {
+    let lines: Vec<&str> = input.lines().collect();
+
+    for (i, l1) in lines.iter().enumerate() {
+        for l2 in &lines[i + 1..] {
+            if l1.len() != l2.len() {
+                continue;
+            }
+
+            let common: String = l1
+                .chars()
+                .zip(l2.chars())
+                .filter_map(|(a, b)| if a == b { Some(a) } else { None })
+                .collect();
+
+            if common.len() == l1.len() - 1 {
+                return Ok(common);
+            }
+        }
+    }
+
+    Err("no boxes differing by exactly one character".to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -88,4 +116,10 @@ mod tests {
     fn part2_example() {
         assert_eq!(part2(INPUT_PART2), "fgij");
     }
+
+    #[test]
+    fn part2_checked_example() {
+        assert_eq!(part2_checked(INPUT_PART2).unwrap().to_string(), "fgij");
+        assert!(part2_checked("abc\ndef").is_err());
+    }
 }
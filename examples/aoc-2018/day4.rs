@@ -54,7 +54,10 @@ impl From<&'static str> for Error {
 
 impl Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> Result<(), fmt::Error> {
-        writeln!(f, "{:?}", self)
+        match self {
+            Error::ParseDateError(e) => writeln!(f, "ParseDateError({})", e),
+            Error::ParseActionError(e) => writeln!(f, "ParseActionError({})", e),
+        }
     }
 }
 impl StdError for Error {}
@@ -97,8 +100,9 @@ fn parse(input: &str) -> Result<Vec<Record>, Error> {
 
 type GuardId = u32;
 type GuardRecord = (Duration, Vec<(NaiveDateTime, NaiveDateTime)>);
+type GuardMap = HashMap<GuardId, GuardRecord>;
 
-fn build_map(records: &[Record]) -> Result<HashMap<GuardId, GuardRecord>, &'static str> {
+fn build_map(records: &[Record]) -> Result<GuardMap, &'static str> {
     let mut guard = None;
     let mut start_sleeping = None;
 
@@ -128,11 +132,11 @@ fn build_map(records: &[Record]) -> Result<HashMap<GuardId, GuardRecord>, &'stat
 }
 
 #[aoc(day4, part1)]
-fn part1(records: &[Record]) -> Result<u32, &'static str> {
+fn part1(records: &[Record]) -> Result<(u32, GuardMap), &'static str> {
     let map = build_map(records)?;
 
     let (guard, (_, sessions)) = map
-        .into_iter()
+        .iter()
         .max_by_key(|(_, d)| d.0)
         .ok_or("maximum sleeping session not found")?;
 
@@ -149,15 +153,13 @@ fn part1(records: &[Record]) -> Result<u32, &'static str> {
         .max_by_key(|&(_, t)| t)
         .ok_or("maximum sleeping minute not found")?;
 
-    Ok(guard * min)
+    Ok((guard * min, map))
 }
 
 #[aoc(day4, part2)]
-fn part2(records: &[Record]) -> Result<u32, &'static str> {
-    let map = build_map(records)?;
-
+fn part2(_records: &[Record], map: &GuardMap) -> Result<u32, &'static str> {
     let (guard, min, _) = map
-        .into_iter()
+        .iter()
         .map(|(guard, (_, sessions))| {
             let (min, count) = (0..60)
                 .map(|m| {
@@ -211,4 +213,13 @@ mod tests {
     fn parse_example() {
         assert!(parse(INPUT).is_ok());
     }
+
+    #[test]
+    fn part2_from_part1_state() {
+        let records = parse(INPUT).unwrap();
+        let (answer, map) = part1(&records).unwrap();
+
+        assert_eq!(answer, 240);
+        assert_eq!(part2(&records, &map), Ok(4455));
+    }
 }
@@ -114,12 +114,13 @@ fn part1(points: &[Point]) -> Option<usize> {
     max_area.map(|(_, size)| size)
 }
 
-#[aoc(day6, part2)]
-fn part2(points: &[Point]) -> Option<usize> {
-    part2_internal(points, 10_000)
+struct Region {
+    /// The region holds the locations closer than this to all points, in total
+    max_distance: u32,
 }
 
-fn part2_internal(points: &[Point], dist: u32) -> Option<usize> {
+#[aoc(day6, part2, params(max_distance = 10_000))]
+fn part2(points: &[Point], region: &Region) -> Option<usize> {
     let (tl, br) = bounds(points);
 
     let tl = tl?;
@@ -128,7 +129,7 @@ fn part2_internal(points: &[Point], dist: u32) -> Option<usize> {
     Some(
         all_points(tl, br)
             .map(|p| points.iter().map(|&other| p.distance(other)).sum())
-            .filter(|&s: &u32| s < dist)
+            .filter(|&s: &u32| s < region.max_distance)
             .count(),
     )
 }
@@ -150,6 +151,6 @@ mod tests {
     fn part2_example() {
         let points = parse(INPUT).unwrap();
 
-        assert_eq!(part2_internal(&points, 32).unwrap(), 16);
+        assert_eq!(part2(&points, &Region { max_distance: 32 }).unwrap(), 16);
     }
 }
@@ -45,6 +45,26 @@ impl Node {
                 .sum()
         }
     }
+
+    fn checksum_and_value(&self) -> (Data, Data) {
+        let children: Vec<_> = self.children.iter().map(|c| c.checksum_and_value()).collect();
+        let self_sum: Data = self.metadata.iter().sum();
+        let checksum = children.iter().map(|&(c, _)| c).sum::<Data>() + self_sum;
+
+        let value = if children.is_empty() {
+            self_sum
+        } else {
+            self.metadata
+                .iter()
+                .filter_map(|i| match i {
+                    0 => None,
+                    _ => children.get(i - 1).map(|&(_, v)| v),
+                })
+                .sum()
+        };
+
+        (checksum, value)
+    }
 }
 
 #[aoc_generator(day8)]
@@ -62,6 +82,11 @@ fn part2(root: &Node) -> Data {
     root.value()
 }
 
+#[aoc(day8, both, OnePass)]
+fn both_one_pass(root: &Node) -> (Data, Data) {
+    root.checksum_and_value()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -81,4 +106,11 @@ mod tests {
 
         assert_eq!(part2(&root), 66);
     }
+
+    #[test]
+    fn both_one_pass_example() {
+        let root = parse(INPUT).unwrap();
+
+        assert_eq!(both_one_pass(&root), (138, 66));
+    }
 }